@@ -0,0 +1,31 @@
+//! `upcow` command-line entry point.
+//!
+//! Today this exposes configuration administration; run `upcow config --help`
+//! for the available subcommands.
+
+use anyhow::Result;
+use clap::{Parser, Subcommand};
+
+use arb_bot::config_file::ConfigCommand;
+
+#[derive(Parser, Debug)]
+#[command(author, version, about = "upcow trading bot admin CLI")]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Generate, validate, and inspect configuration.
+    #[command(subcommand)]
+    Config(ConfigCommand),
+}
+
+fn main() -> Result<()> {
+    dotenvy::dotenv().ok();
+    let cli = Cli::parse();
+    match cli.command {
+        Command::Config(cmd) => cmd.run(),
+    }
+}
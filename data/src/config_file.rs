@@ -3,8 +3,13 @@
 //! Reads configuration from config.yml file with environment variable overrides
 
 use anyhow::{Context, Result};
+use arc_swap::ArcSwap;
 use serde::{Deserialize, Serialize};
-use std::path::PathBuf;
+use std::collections::HashMap;
+use std::net::{IpAddr, SocketAddr};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
 
 /// Main configuration structure
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -16,12 +21,42 @@ pub struct AppConfig {
     pub btc_1h_pair_trading: Option<Btc1hPairTradingConfig>,
     pub poly_sniper: Option<PolySniperConfig>,
     pub circuit_breaker: Option<CircuitBreakerConfig>,
+    #[serde(default)]
+    pub dns: Option<DnsConfig>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ProxyConfig {
     pub enabled: bool,
-    pub address: String,
+    /// Upstream used for every scheme unless a per-scheme target overrides it.
+    #[serde(default)]
+    pub all: Option<String>,
+    /// Upstream for `http://` requests only.
+    #[serde(default)]
+    pub http: Option<String>,
+    /// Upstream for `https://` requests only.
+    #[serde(default)]
+    pub https: Option<String>,
+    #[serde(default)]
+    pub username: Option<String>,
+    #[serde(default)]
+    pub password: Option<String>,
+    /// Hostnames to reach directly, bypassing the proxy. Supports exact hosts
+    /// and leading-dot suffixes (e.g. `.polygon.io`). Merged with `NO_PROXY`.
+    #[serde(default)]
+    pub no_proxy: Vec<String>,
+}
+
+/// Static DNS overrides wired into the HTTP client builders.
+///
+/// Each entry pins a hostname to a specific IP (optionally `ip:port`), letting
+/// a latency-sensitive operator bypass a slow or hijacked system resolver for
+/// hosts like `clob.polymarket.com` or the Polygon RPC endpoint.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DnsConfig {
+    /// Map of `hostname -> IP` (or `IP:port`) overrides.
+    #[serde(default)]
+    pub overrides: HashMap<String, String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -89,6 +124,25 @@ impl AppConfig {
         Ok(config)
     }
 
+    /// Validate that configured proxy targets use a supported scheme.
+    ///
+    /// Lightweight structural check used by [`ConfigWatcher`] before swapping a
+    /// freshly-loaded config in; the `upcow config validate` subcommand layers
+    /// richer field-level reporting on top of this.
+    pub fn validate(&self) -> Result<()> {
+        if self.proxy.enabled {
+            for target in [&self.proxy.all, &self.proxy.http, &self.proxy.https]
+                .into_iter()
+                .flatten()
+                .filter(|s| !s.is_empty())
+            {
+                validate_proxy_scheme(&normalize_proxy_url(target))?;
+            }
+        }
+        self.resolved_dns()?;
+        Ok(())
+    }
+
     /// Apply environment variable overrides
     fn apply_env_overrides(&mut self) {
         // Proxy settings
@@ -96,7 +150,13 @@ impl AppConfig {
             self.proxy.enabled = enabled == "1" || enabled == "true";
         }
         if let Ok(addr) = std::env::var("PROXY_ADDRESS") {
-            self.proxy.address = addr;
+            self.proxy.all = Some(addr);
+        }
+        if let Ok(user) = std::env::var("PROXY_USERNAME") {
+            self.proxy.username = Some(user);
+        }
+        if let Ok(pass) = std::env::var("PROXY_PASSWORD") {
+            self.proxy.password = Some(pass);
         }
 
         // Polymarket settings
@@ -119,15 +179,213 @@ impl AppConfig {
         if let Ok(log_level) = std::env::var("RUST_LOG") {
             self.trading.log_level = log_level;
         }
+
+        // DNS overrides: DNS_OVERRIDES="host1=1.2.3.4,host2=5.6.7.8:443"
+        if let Ok(raw) = std::env::var("DNS_OVERRIDES") {
+            let dns = self.dns.get_or_insert_with(DnsConfig::default);
+            for entry in raw.split(',') {
+                let entry = entry.trim();
+                if entry.is_empty() {
+                    continue;
+                }
+                if let Some((host, ip)) = entry.split_once('=') {
+                    dns.overrides.insert(host.trim().to_string(), ip.trim().to_string());
+                }
+            }
+        }
     }
 
-    /// Get proxy URL if enabled
+    /// Parse and validate the configured DNS overrides into `(host, addr)`
+    /// pairs, erroring clearly on any entry that is not a valid `IpAddr` or
+    /// `SocketAddr`.
+    fn resolved_dns(&self) -> Result<Vec<(String, SocketAddr)>> {
+        let Some(dns) = &self.dns else { return Ok(Vec::new()) };
+        let mut out = Vec::with_capacity(dns.overrides.len());
+        for (host, target) in &dns.overrides {
+            out.push((host.clone(), parse_dns_target(host, target)?));
+        }
+        Ok(out)
+    }
+
+    /// Get a single proxy URL if enabled, preferring the `all` target.
+    ///
+    /// Honors an explicit URI scheme (`http`, `https`, `socks4`, `socks4a`,
+    /// `socks5`, `socks5h`) and only prepends `http://` when no `://` is
+    /// present. When the proxy is disabled or no target is configured, falls
+    /// back to the standard `ALL_PROXY`/`HTTPS_PROXY`/`HTTP_PROXY` environment
+    /// variables before giving up. This is the scalar view used by the
+    /// URL-only client builders; the per-scheme routing lives in
+    /// [`ProxyConfig::configure_builder`].
     pub fn proxy_url(&self) -> Option<String> {
         if self.proxy.enabled {
-            Some(format!("http://{}", self.proxy.address))
+            if let Some(target) = self
+                .proxy
+                .all
+                .as_ref()
+                .or(self.proxy.https.as_ref())
+                .or(self.proxy.http.as_ref())
+                .filter(|s| !s.is_empty())
+            {
+                return Some(normalize_proxy_url(target));
+            }
+        }
+
+        // Fall back to standard proxy environment variables
+        for var in ["ALL_PROXY", "all_proxy", "HTTPS_PROXY", "https_proxy", "HTTP_PROXY", "http_proxy"] {
+            if let Ok(val) = std::env::var(var) {
+                if !val.is_empty() {
+                    return Some(normalize_proxy_url(&val));
+                }
+            }
+        }
+
+        None
+    }
+}
+
+impl ProxyConfig {
+    /// Merge the configured `no_proxy` list with the `NO_PROXY` environment
+    /// variable (comma or whitespace separated), lower-cased for matching.
+    fn merged_no_proxy(&self) -> Vec<String> {
+        let mut hosts: Vec<String> = self
+            .no_proxy
+            .iter()
+            .map(|h| h.trim().to_lowercase())
+            .filter(|h| !h.is_empty())
+            .collect();
+
+        for var in ["NO_PROXY", "no_proxy"] {
+            if let Ok(val) = std::env::var(var) {
+                for entry in val.split([',', ' ']) {
+                    let entry = entry.trim().to_lowercase();
+                    if !entry.is_empty() && !hosts.contains(&entry) {
+                        hosts.push(entry);
+                    }
+                }
+            }
+        }
+
+        hosts
+    }
+
+    /// Register per-scheme proxies and the `no_proxy` bypass list on a client
+    /// builder. Returns the builder unchanged when the proxy is disabled or no
+    /// target is configured.
+    pub fn configure_builder(&self, mut builder: reqwest::ClientBuilder) -> Result<reqwest::ClientBuilder> {
+        if !self.enabled {
+            return Ok(builder);
+        }
+
+        let no_proxy = self.merged_no_proxy();
+        // `reqwest` dispatches to the first registered proxy whose interceptor
+        // matches a request, so the per-scheme overrides must be registered
+        // before `all` — otherwise `all` would shadow them for every request.
+        let targets = [
+            (self.http.as_ref(), ProxyKind::Http),
+            (self.https.as_ref(), ProxyKind::Https),
+            (self.all.as_ref(), ProxyKind::All),
+        ];
+
+        for (target, kind) in targets {
+            let Some(target) = target.filter(|s| !s.is_empty()) else { continue };
+            let proxy = self.build_scheme_proxy(target, kind, &no_proxy)?;
+            builder = builder.proxy(proxy);
+        }
+
+        Ok(builder)
+    }
+
+    /// Build a single scheme-scoped `reqwest::Proxy` with `no_proxy` bypass and
+    /// optional basic-auth credentials attached.
+    fn build_scheme_proxy(
+        &self,
+        target: &str,
+        kind: ProxyKind,
+        no_proxy: &[String],
+    ) -> Result<reqwest::Proxy> {
+        let url = normalize_proxy_url(target);
+        validate_proxy_scheme(&url)?;
+        let upstream = reqwest::Url::parse(&url)
+            .with_context(|| format!("invalid proxy URL '{}'", url))?;
+        let no_proxy = no_proxy.to_vec();
+
+        let mut proxy = reqwest::Proxy::custom(move |req_url| {
+            match req_url.scheme() {
+                "http" if kind == ProxyKind::Https => return None,
+                "https" if kind == ProxyKind::Http => return None,
+                _ => {}
+            }
+            match req_url.host_str() {
+                Some(host) if host_bypassed(host, &no_proxy) => None,
+                _ => Some(upstream.clone()),
+            }
+        });
+
+        if let (Some(user), Some(pass)) = (self.username.as_ref(), self.password.as_ref()) {
+            proxy = proxy.basic_auth(user, pass);
+        }
+
+        Ok(proxy)
+    }
+}
+
+/// Which request schemes a configured proxy target applies to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ProxyKind {
+    All,
+    Http,
+    Https,
+}
+
+/// Match a request host against a `no_proxy` entry, supporting exact hosts and
+/// leading-dot suffix matching (`.polygon.io` matches `api.polygon.io`).
+fn host_bypassed(host: &str, no_proxy: &[String]) -> bool {
+    let host = host.to_lowercase();
+    no_proxy.iter().any(|entry| {
+        if let Some(suffix) = entry.strip_prefix('.') {
+            host == suffix || host.ends_with(entry.as_str())
         } else {
-            None
+            host == *entry
         }
+    })
+}
+
+/// Proxy schemes we know how to route through `reqwest`.
+const SUPPORTED_PROXY_SCHEMES: &[&str] =
+    &["http", "https", "socks4", "socks4a", "socks5", "socks5h"];
+
+/// Prepend `http://` to a proxy address only when it carries no explicit scheme.
+fn normalize_proxy_url(address: &str) -> String {
+    if address.contains("://") {
+        address.to_string()
+    } else {
+        format!("http://{}", address)
+    }
+}
+
+/// Build a `reqwest::Proxy` from a URL, validating the scheme and attaching
+/// basic-auth credentials when both a username and password are supplied.
+fn build_proxy(url: &str, username: Option<&str>, password: Option<&str>) -> Result<reqwest::Proxy> {
+    validate_proxy_scheme(url)?;
+    let proxy = reqwest::Proxy::all(url)?;
+    match (username, password) {
+        (Some(user), Some(pass)) => Ok(proxy.basic_auth(user, pass)),
+        _ => Ok(proxy),
+    }
+}
+
+/// Validate that a proxy URL uses a scheme `reqwest` can route through.
+fn validate_proxy_scheme(url: &str) -> Result<()> {
+    let scheme = url.split("://").next().unwrap_or("").to_lowercase();
+    if SUPPORTED_PROXY_SCHEMES.contains(&scheme.as_str()) {
+        Ok(())
+    } else {
+        anyhow::bail!(
+            "unsupported proxy scheme '{}' in '{}' (supported: {})",
+            scheme,
+            url,
+            SUPPORTED_PROXY_SCHEMES.join(", ")
+        )
     }
 }
 
@@ -136,7 +394,12 @@ impl Default for AppConfig {
         Self {
             proxy: ProxyConfig {
                 enabled: true,
-                address: "127.0.0.1:15236".to_string(),
+                all: Some("127.0.0.1:15236".to_string()),
+                http: None,
+                https: None,
+                username: None,
+                password: None,
+                no_proxy: Vec::new(),
             },
             polymarket: PolymarketConfig {
                 private_key: String::new(),
@@ -170,14 +433,68 @@ impl Default for AppConfig {
                 max_consecutive_errors: 5,
                 cooldown_secs: 300,
             }),
+            dns: None,
+        }
+    }
+}
+
+/// Parse a DNS override target into a `SocketAddr`.
+///
+/// Accepts a bare `IpAddr` (port defaults to 0 — `reqwest` keeps the request's
+/// original port) or a full `IpAddr:port`.
+fn parse_dns_target(host: &str, target: &str) -> Result<SocketAddr> {
+    if let Ok(addr) = target.parse::<SocketAddr>() {
+        return Ok(addr);
+    }
+    let ip = target.parse::<IpAddr>().with_context(|| {
+        format!("invalid DNS override for '{}': '{}' is not an IP or socket address", host, target)
+    })?;
+    Ok(SocketAddr::new(ip, 0))
+}
+
+/// Apply static host -> addr overrides to a client builder.
+fn apply_dns_overrides(mut builder: reqwest::ClientBuilder, overrides: &[(String, SocketAddr)]) -> reqwest::ClientBuilder {
+    for (host, addr) in overrides {
+        builder = builder.resolve(host, *addr);
+    }
+    builder
+}
+
+/// Read and validate DNS overrides from the `DNS_OVERRIDES` environment
+/// variable, used by the URL-only client builders.
+fn env_dns_overrides() -> Result<Vec<(String, SocketAddr)>> {
+    let Ok(raw) = std::env::var("DNS_OVERRIDES") else { return Ok(Vec::new()) };
+    let mut out = Vec::new();
+    for entry in raw.split(',') {
+        let entry = entry.trim();
+        if entry.is_empty() {
+            continue;
         }
+        let (host, target) = entry
+            .split_once('=')
+            .with_context(|| format!("malformed DNS_OVERRIDES entry '{}' (expected host=ip)", entry))?;
+        out.push((host.trim().to_string(), parse_dns_target(host.trim(), target.trim())?));
     }
+    Ok(out)
+}
+
+/// Read proxy basic-auth credentials from the environment, used by the
+/// URL-only client builders that don't carry an `AppConfig`.
+fn proxy_env_credentials() -> (Option<String>, Option<String>) {
+    (
+        std::env::var("PROXY_USERNAME").ok().filter(|s| !s.is_empty()),
+        std::env::var("PROXY_PASSWORD").ok().filter(|s| !s.is_empty()),
+    )
 }
 
 /// Helper function to create reqwest::Client with proxy support
 pub fn create_http_client() -> Result<reqwest::Client> {
     let config = AppConfig::load()?;
-    create_http_client_with_proxy(config.proxy_url())
+    let builder = reqwest::Client::builder()
+        .timeout(std::time::Duration::from_secs(10));
+    let builder = config.proxy.configure_builder(builder)?;
+    let builder = apply_dns_overrides(builder, &config.resolved_dns()?);
+    Ok(builder.build()?)
 }
 
 /// Create reqwest::Client with optional proxy
@@ -186,8 +503,10 @@ pub fn create_http_client_with_proxy(proxy_url: Option<String>) -> Result<reqwes
         .timeout(std::time::Duration::from_secs(10));
 
     if let Some(proxy) = proxy_url {
-        builder = builder.proxy(reqwest::Proxy::all(&proxy)?);
+        let (user, pass) = proxy_env_credentials();
+        builder = builder.proxy(build_proxy(&proxy, user.as_deref(), pass.as_deref())?);
     }
+    builder = apply_dns_overrides(builder, &env_dns_overrides()?);
 
     Ok(builder.build()?)
 }
@@ -198,8 +517,10 @@ pub fn create_http_client_with_timeout(timeout_secs: u64, proxy_url: Option<Stri
         .timeout(std::time::Duration::from_secs(timeout_secs));
 
     if let Some(proxy) = proxy_url {
-        builder = builder.proxy(reqwest::Proxy::all(&proxy)?);
+        let (user, pass) = proxy_env_credentials();
+        builder = builder.proxy(build_proxy(&proxy, user.as_deref(), pass.as_deref())?);
     }
+    builder = apply_dns_overrides(builder, &env_dns_overrides()?);
 
     Ok(builder.build()?)
 }
@@ -207,13 +528,9 @@ pub fn create_http_client_with_timeout(timeout_secs: u64, proxy_url: Option<Stri
 /// Create reqwest::Client builder with proxy support
 pub fn create_client_builder() -> Result<reqwest::ClientBuilder> {
     let config = AppConfig::load()?;
-    let mut builder = reqwest::Client::builder();
-    
-    if let Some(proxy) = config.proxy_url() {
-        builder = builder.proxy(reqwest::Proxy::all(&proxy)?);
-    }
-    
-    Ok(builder)
+    let builder = reqwest::Client::builder();
+    let builder = config.proxy.configure_builder(builder)?;
+    Ok(apply_dns_overrides(builder, &config.resolved_dns()?))
 }
 
 /// Get proxy URL from config
@@ -221,3 +538,322 @@ pub fn get_proxy_url() -> Option<String> {
     AppConfig::load().ok().and_then(|c| c.proxy_url())
 }
 
+// === CLI surface ===
+
+const REDACTED: &str = "***REDACTED***";
+
+/// `upcow config` subcommands for bootstrapping and sanity-checking
+/// configuration before going live.
+#[derive(Debug, clap::Subcommand)]
+pub enum ConfigCommand {
+    /// Write a commented default `config.yml` from [`AppConfig::default`].
+    Init {
+        /// Overwrite an existing config.yml.
+        #[arg(long)]
+        force: bool,
+    },
+    /// Load config.yml plus env overrides and report any missing/invalid fields.
+    Validate,
+    /// Print the effective merged config with secrets redacted.
+    Show,
+}
+
+impl ConfigCommand {
+    /// Execute the subcommand.
+    pub fn run(self) -> Result<()> {
+        match self {
+            ConfigCommand::Init { force } => config_init(force),
+            ConfigCommand::Validate => config_validate(),
+            ConfigCommand::Show => config_show(),
+        }
+    }
+}
+
+fn config_init(force: bool) -> Result<()> {
+    let path = PathBuf::from("config.yml");
+    if path.exists() && !force {
+        anyhow::bail!("config.yml already exists (use --force to overwrite)");
+    }
+
+    let default = AppConfig::default();
+    let yaml = serde_yaml::to_string(&default).context("failed to serialize default config")?;
+    let contents = format!(
+        "# upcow configuration\n\
+         #\n\
+         # Generated by `upcow config init`. Secrets (polymarket.private_key,\n\
+         # polygon.api_key, proxy.password) can also be supplied via environment\n\
+         # variables: POLY_PRIVATE_KEY, POLYGON_API_KEY, PROXY_PASSWORD, etc.\n\
+         #\n{}",
+        yaml
+    );
+    std::fs::write(&path, contents).context("failed to write config.yml")?;
+    println!("Wrote default config to {}", path.display());
+    Ok(())
+}
+
+fn config_validate() -> Result<()> {
+    let config = AppConfig::load().context("failed to load config.yml")?;
+    let problems = config.collect_problems();
+
+    if problems.is_empty() {
+        println!("config.yml is valid");
+        Ok(())
+    } else {
+        for p in &problems {
+            eprintln!("  ✗ {}", p);
+        }
+        anyhow::bail!("{} configuration problem(s) found", problems.len())
+    }
+}
+
+fn config_show() -> Result<()> {
+    let config = AppConfig::load().context("failed to load config.yml")?;
+    let redacted = config.redacted();
+    let yaml = serde_yaml::to_string(&redacted).context("failed to serialize config")?;
+    print!("{}", yaml);
+    Ok(())
+}
+
+impl AppConfig {
+    /// Return a clone with all secrets replaced by a redaction marker, suitable
+    /// for printing. Empty secrets are left empty so the output still signals
+    /// "not set".
+    pub fn redacted(&self) -> AppConfig {
+        let mut c = self.clone();
+        if !c.polymarket.private_key.is_empty() {
+            c.polymarket.private_key = REDACTED.to_string();
+        }
+        if !c.polygon.api_key.is_empty() {
+            c.polygon.api_key = REDACTED.to_string();
+        }
+        if let Some(pass) = c.proxy.password.as_mut() {
+            if !pass.is_empty() {
+                *pass = REDACTED.to_string();
+            }
+        }
+        c
+    }
+
+    /// Collect human-readable descriptions of every invalid or missing field.
+    ///
+    /// Unlike [`validate`](AppConfig::validate), which fails fast on the first
+    /// structural error, this gathers all problems so `config validate` can
+    /// report them together.
+    pub fn collect_problems(&self) -> Vec<String> {
+        let mut problems = Vec::new();
+
+        if self.polymarket.private_key.is_empty() {
+            problems.push("polymarket.private_key is empty".to_string());
+        }
+
+        if self.proxy.enabled {
+            for target in [&self.proxy.all, &self.proxy.http, &self.proxy.https]
+                .into_iter()
+                .flatten()
+                .filter(|s| !s.is_empty())
+            {
+                if let Err(e) = validate_proxy_scheme(&normalize_proxy_url(target)) {
+                    problems.push(format!("proxy target '{}': {}", target, e));
+                }
+            }
+        }
+
+        if let Err(e) = self.resolved_dns() {
+            problems.push(e.to_string());
+        }
+
+        if let Some(cb) = &self.circuit_breaker {
+            if cb.max_position_per_market < 0 {
+                problems.push("circuit_breaker.max_position_per_market is negative".to_string());
+            }
+            if cb.max_total_position < 0 {
+                problems.push("circuit_breaker.max_total_position is negative".to_string());
+            }
+            if cb.max_daily_loss < 0.0 {
+                problems.push("circuit_breaker.max_daily_loss is negative".to_string());
+            }
+        }
+
+        if let Some(sniper) = &self.poly_sniper {
+            if !(0.0..=1000.0).contains(&sniper.vol) {
+                problems.push(format!(
+                    "poly_sniper.vol {} out of range (expected 0..=1000)",
+                    sniper.vol
+                ));
+            }
+            if !(0..=100).contains(&sniper.edge) {
+                problems.push(format!(
+                    "poly_sniper.edge {} out of range (expected 0..=100)",
+                    sniper.edge
+                ));
+            }
+        }
+
+        problems
+    }
+}
+
+// === Hot-reload ===
+
+/// A live, atomically-swappable view of the effective [`AppConfig`] and the
+/// shared `reqwest::Client` built from it.
+///
+/// Clone it freely and hand copies to running strategies: each [`config`] /
+/// [`client`] call observes the latest value the [`ConfigWatcher`] has
+/// published, so edge/size/profit-target tweaks in `config.yml` take effect
+/// without a restart.
+///
+/// [`config`]: ConfigHandle::config
+/// [`client`]: ConfigHandle::client
+#[derive(Clone)]
+pub struct ConfigHandle {
+    config: Arc<ArcSwap<AppConfig>>,
+    client: Arc<ArcSwap<reqwest::Client>>,
+}
+
+impl ConfigHandle {
+    /// Current effective configuration.
+    pub fn config(&self) -> Arc<AppConfig> {
+        self.config.load_full()
+    }
+
+    /// Current HTTP client (rebuilt on proxy/timeout changes).
+    pub fn client(&self) -> Arc<reqwest::Client> {
+        self.client.load_full()
+    }
+}
+
+/// Watches `config.yml` for changes and republishes the config plus a freshly
+/// built HTTP client through a shared [`ConfigHandle`].
+///
+/// A partial or invalid write keeps the previous good config in place; only a
+/// parse+validate cycle that fully succeeds is swapped in.
+pub struct ConfigWatcher {
+    handle: ConfigHandle,
+    path: PathBuf,
+    last_mtime: Option<std::time::SystemTime>,
+    last_config: Arc<AppConfig>,
+}
+
+impl ConfigWatcher {
+    /// Load the initial config and build the first client, returning a handle
+    /// for consumers and the watcher to spawn.
+    pub fn new() -> Result<(ConfigHandle, Self)> {
+        Self::with_path(PathBuf::from("config.yml"))
+    }
+
+    /// Like [`new`](Self::new) but polling the modification time of `path`
+    /// to trigger reloads. The config itself is still read by
+    /// [`AppConfig::load`], which always loads `config.yml`.
+    pub fn with_path(path: PathBuf) -> Result<(ConfigHandle, Self)> {
+        let config = AppConfig::load()?;
+        config.validate()?;
+        let client = build_client(&config)?;
+
+        let config = Arc::new(config);
+        let handle = ConfigHandle {
+            config: Arc::new(ArcSwap::from(config.clone())),
+            client: Arc::new(ArcSwap::from(Arc::new(client))),
+        };
+        let last_mtime = mtime(&path);
+
+        Ok((
+            handle.clone(),
+            Self {
+                handle,
+                path,
+                last_mtime,
+                last_config: config,
+            },
+        ))
+    }
+
+    /// Spawn the mtime-polling reload loop as a background task.
+    pub fn spawn(mut self, poll_interval: Duration) {
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(poll_interval);
+            loop {
+                ticker.tick().await;
+                let current = mtime(&self.path);
+                if current == self.last_mtime {
+                    continue;
+                }
+                self.last_mtime = current;
+                if let Err(e) = self.reload() {
+                    tracing::warn!("[CONFIG] Reload rejected, keeping previous config: {}", e);
+                }
+            }
+        });
+    }
+
+    /// Re-load, validate and atomically publish the config and client,
+    /// logging which top-level sections changed.
+    fn reload(&mut self) -> Result<()> {
+        let mut config = AppConfig::load()?;
+        config.apply_env_overrides();
+        config.validate()?;
+
+        let proxy_changed = !proxy_eq(&self.last_config.proxy, &config.proxy);
+        log_changes(&self.last_config, &config);
+
+        let config = Arc::new(config);
+        // Rebuild the shared client only when proxy/timeout-relevant fields move.
+        if proxy_changed {
+            let client = build_client(&config)?;
+            self.handle.client.store(Arc::new(client));
+            tracing::info!("[CONFIG] Rebuilt HTTP client after proxy change");
+        }
+        self.handle.config.store(config.clone());
+        self.last_config = config;
+        tracing::info!("[CONFIG] Reloaded config.yml");
+        Ok(())
+    }
+}
+
+/// Build the shared HTTP client from a config's proxy settings.
+fn build_client(config: &AppConfig) -> Result<reqwest::Client> {
+    let builder = reqwest::Client::builder().timeout(Duration::from_secs(10));
+    let builder = config.proxy.configure_builder(builder)?;
+    let builder = apply_dns_overrides(builder, &config.resolved_dns()?);
+    Ok(builder.build()?)
+}
+
+fn mtime(path: &Path) -> Option<std::time::SystemTime> {
+    std::fs::metadata(path).ok().and_then(|m| m.modified().ok())
+}
+
+/// Compare the proxy-relevant fields that require a client rebuild.
+fn proxy_eq(a: &ProxyConfig, b: &ProxyConfig) -> bool {
+    a.enabled == b.enabled
+        && a.all == b.all
+        && a.http == b.http
+        && a.https == b.https
+        && a.username == b.username
+        && a.password == b.password
+        && a.no_proxy == b.no_proxy
+}
+
+/// Log which top-level config sections changed between two loads.
+fn log_changes(old: &AppConfig, new: &AppConfig) {
+    let mut changed = Vec::new();
+    if !proxy_eq(&old.proxy, &new.proxy) {
+        changed.push("proxy");
+    }
+    // Optional/plain sections are compared via their debug form.
+    macro_rules! check {
+        ($field:ident, $name:literal) => {
+            if format!("{:?}", old.$field) != format!("{:?}", new.$field) {
+                changed.push($name);
+            }
+        };
+    }
+    check!(trading, "trading");
+    check!(poly_sniper, "poly_sniper");
+    check!(btc_1h_pair_trading, "btc_1h_pair_trading");
+    check!(circuit_breaker, "circuit_breaker");
+    check!(polygon, "polygon");
+    if !changed.is_empty() {
+        tracing::info!("[CONFIG] Changed sections: {}", changed.join(", "));
+    }
+}
+
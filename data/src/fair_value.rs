@@ -31,7 +31,37 @@ fn norm_cdf(x: f64) -> f64 {
 ///
 /// # Returns
 /// (yes_probability, no_probability) as decimals 0.0-1.0
+///
+/// A zero-carry wrapper over [`calc_fair_value_with_carry`] (`r = q = 0.0`),
+/// which is the right assumption for crypto minutes-out contracts but not
+/// for instruments with a meaningful funding rate or forward.
 pub fn calc_fair_value(spot: f64, strike: f64, minutes_remaining: f64, annual_vol: f64) -> (f64, f64) {
+    calc_fair_value_with_carry(spot, strike, minutes_remaining, annual_vol, 0.0, 0.0)
+}
+
+/// Calculate fair value for a binary option with a risk-free rate and
+/// carry/funding yield (returns discounted price, not a bare probability).
+///
+/// # Arguments
+/// * `spot` - Current spot price (e.g., 105000.0 for BTC)
+/// * `strike` - Strike price (e.g., 104500.0)
+/// * `minutes_remaining` - Minutes until expiration (0-15 typically)
+/// * `annual_vol` - Annualized volatility as decimal (e.g., 0.50 for 50%)
+/// * `r` - Continuously-compounded risk-free rate, annualized
+/// * `q` - Continuously-compounded carry/funding yield, annualized
+///
+/// # Returns
+/// (yes_price, no_price): each leg's risk-neutral expected payoff
+/// (0.0-1.0), discounted to today by `exp(-r·T)`. With `r = 0` this is
+/// numerically identical to `calc_fair_value`'s bare probability.
+pub fn calc_fair_value_with_carry(
+    spot: f64,
+    strike: f64,
+    minutes_remaining: f64,
+    annual_vol: f64,
+    r: f64,
+    q: f64,
+) -> (f64, f64) {
     // Edge cases
     if minutes_remaining <= 0.0 {
         if spot > strike {
@@ -41,27 +71,28 @@ pub fn calc_fair_value(spot: f64, strike: f64, minutes_remaining: f64, annual_vo
         }
     }
 
+    let time_years = minutes_remaining / 525960.0;
+    let discount = (-r * time_years).exp();
+
     if annual_vol <= 0.0 {
         if spot > strike {
-            return (1.0, 0.0);
+            return (discount, 0.0);
         } else {
-            return (0.0, 1.0);
+            return (0.0, discount);
         }
     }
 
-    // Convert minutes to years: minutes / (365.25 * 24 * 60)
-    let time_years = minutes_remaining / 525960.0;
-
-    // d2 = [ln(S/K) - σ²T/2] / (σ√T)
+    // d2 = [ln(S/K) + (r - q - σ²/2)·T] / (σ√T)
     let sqrt_t = time_years.sqrt();
     let log_ratio = (spot / strike).ln();
-    let d2 = (log_ratio - 0.5 * annual_vol.powi(2) * time_years) / (annual_vol * sqrt_t);
+    let drift = (r - q - 0.5 * annual_vol.powi(2)) * time_years;
+    let d2 = (log_ratio + drift) / (annual_vol * sqrt_t);
 
-    // P(YES) = N(d2) for binary option
-    let yes_prob = norm_cdf(d2);
-    let no_prob = 1.0 - yes_prob;
+    // Discounted P(YES) = e^{-rT}·N(d2) for binary option
+    let yes_price = discount * norm_cdf(d2);
+    let no_price = discount - yes_price;
 
-    (yes_prob, no_prob)
+    (yes_price, no_price)
 }
 
 /// Calculate fair value in cents (0-100)
@@ -75,6 +106,299 @@ pub fn calc_fair_value_cents(spot: f64, strike: f64, minutes_remaining: f64, ann
 /// Default annualized volatility for BTC/ETH (measured from recent 15-min bars)
 pub const DEFAULT_VOL: f64 = 0.50;
 
+// === Greeks ===
+
+/// Standard normal PDF: n(x) = exp(-x²/2) / √(2π)
+fn norm_pdf(x: f64) -> f64 {
+    const SQRT_2PI: f64 = 2.5066282746310002;
+    (-0.5 * x * x).exp() / SQRT_2PI
+}
+
+/// Sensitivities of the YES leg's price to spot, vol, and time, for sizing
+/// positions near expiry where a digital's payoff can flip on a tiny move.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Greeks {
+    /// Change in YES price per $1 move in spot.
+    pub delta: f64,
+    /// Change in delta per $1 move in spot.
+    pub gamma: f64,
+    /// Change in YES price per 1.00 (100 vol points) change in annual vol.
+    pub vega: f64,
+    /// Change in YES price per day of time decay, as the price moves toward
+    /// its terminal 0/1 value: positive at or above the strike (rising toward
+    /// 1), negative once spot is far enough below it (falling toward 0).
+    pub theta: f64,
+    /// `theta` rescaled to change per minute, since these contracts live
+    /// 0-15 minutes and a per-day figure is hard to reason about at that
+    /// horizon.
+    pub theta_per_minute: f64,
+}
+
+/// Analytic Black-Scholes Greeks for the YES leg of a cash-or-nothing digital,
+/// mirroring the `greeks` crate's formulas but specialized to `N(d2)` pricing
+/// (no payoff scaling by spot, as a vanilla option's delta/gamma would have).
+///
+/// Given `d2` from [`calc_fair_value`] and `d1 = d2 + σ√T`:
+/// * `delta = n(d2) / (S·σ·√T)`
+/// * `gamma = -n(d2)·d2 / (S²·σ²·T)`
+/// * `vega  = -n(d2)·d1 / σ`
+/// * `theta` is the per-year rate of change of `N(d2)` as `T` shrinks,
+///   rescaled to per-day (and `theta_per_minute` to per-minute).
+///
+/// Returns all-zero Greeks at or past expiry, where the payoff is a step
+/// function and these derivatives are undefined.
+pub fn calc_greeks(spot: f64, strike: f64, minutes_remaining: f64, annual_vol: f64) -> Greeks {
+    if minutes_remaining <= 0.0 || annual_vol <= 0.0 {
+        return Greeks {
+            delta: 0.0,
+            gamma: 0.0,
+            vega: 0.0,
+            theta: 0.0,
+            theta_per_minute: 0.0,
+        };
+    }
+
+    let time_years = minutes_remaining / 525960.0;
+    let sqrt_t = time_years.sqrt();
+    let log_ratio = (spot / strike).ln();
+    let d2 = (log_ratio - 0.5 * annual_vol.powi(2) * time_years) / (annual_vol * sqrt_t);
+    let d1 = d2 + annual_vol * sqrt_t;
+    let pdf = norm_pdf(d2);
+
+    let delta = pdf / (spot * annual_vol * sqrt_t);
+    let gamma = -pdf * d2 / (spot.powi(2) * annual_vol.powi(2) * time_years);
+    let vega = -pdf * d1 / annual_vol;
+
+    // d(N(d2))/dT via the chain rule on d2(T), holding spot/strike/vol fixed:
+    // dd2/dT = -log_ratio / (σ·T^1.5·2) - σ / (4·√T). `theta` is the negative
+    // of this (time passing, not time remaining, is the convention callers
+    // want), per year; rescale to per-day and per-minute.
+    let dd2_dt = -log_ratio / (2.0 * annual_vol * time_years * sqrt_t) - annual_vol / (4.0 * sqrt_t);
+    let theta_per_year = -pdf * dd2_dt;
+    let theta = theta_per_year / 365.25;
+    let theta_per_minute = theta_per_year / 525960.0;
+
+    Greeks {
+        delta,
+        gamma,
+        vega,
+        theta,
+        theta_per_minute,
+    }
+}
+
+// === Binomial tree engine ===
+
+/// Barrier behavior for the binomial engine's path-dependent payoffs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BarrierKind {
+    /// Plain European cash-or-nothing digital: payoff depends only on the
+    /// terminal spot, the same contract `calc_fair_value` prices in closed
+    /// form.
+    European,
+    /// YES pays 1 if spot ever crosses the strike before expiry, 0 otherwise
+    /// (a one-touch digital).
+    Touch,
+    /// YES pays 1 only if spot never crosses the strike before expiry (a
+    /// no-touch digital) — the complement of `Touch`.
+    NoTouch,
+}
+
+/// Cox-Ross-Rubinstein binomial tree, a second pricing backend alongside the
+/// closed-form `calc_fair_value`, for payoffs the Black-Scholes formula can't
+/// express (touch/no-touch barriers). `steps` is the number of tree steps
+/// over `minutes_remaining`; the risk-neutral rate is fixed at 0, matching
+/// `calc_fair_value`'s assumption.
+///
+/// Builds `dt = T/steps`, `u = exp(σ√dt)`, `d = 1/u`, and the risk-neutral
+/// `p = (1 - d)/(u - d)` (the `r = 0` case of `(e^{r·dt} - d)/(u - d)`,
+/// clamped to `[0, 1]`), seeds terminal nodes with the digital payoff, and
+/// rolls values back via `value = p·value_up + (1-p)·value_down`. For
+/// `Touch`/`NoTouch`, a node whose spot has already crossed the strike is
+/// locked to its resolved payoff (1.0/0.0 respectively) instead of being
+/// computed from its children during rollback, since the barrier condition
+/// is already decided for every path reaching it.
+pub fn calc_fair_value_binomial(
+    spot: f64,
+    strike: f64,
+    minutes_remaining: f64,
+    annual_vol: f64,
+    steps: usize,
+    barrier: BarrierKind,
+) -> (f64, f64) {
+    if minutes_remaining <= 0.0 || annual_vol <= 0.0 || steps == 0 {
+        let yes = if spot > strike { 1.0 } else { 0.0 };
+        return (yes, 1.0 - yes);
+    }
+
+    let time_years = minutes_remaining / 525960.0;
+    let dt = time_years / steps as f64;
+    let u = (annual_vol * dt.sqrt()).exp();
+    let d = 1.0 / u;
+    let p = ((1.0 - d) / (u - d)).clamp(0.0, 1.0);
+
+    let below_strike = spot < strike;
+    let breached = |node_spot: f64| -> bool {
+        if below_strike { node_spot >= strike } else { node_spot <= strike }
+    };
+
+    // Terminal layer: node j (0..=steps) has spot * u^j * d^(steps-j).
+    let mut values: Vec<f64> = (0..=steps)
+        .map(|j| {
+            let node_spot = spot * u.powi(j as i32) * d.powi((steps - j) as i32);
+            match barrier {
+                BarrierKind::European => if node_spot > strike { 1.0 } else { 0.0 },
+                BarrierKind::Touch => if breached(node_spot) { 1.0 } else { 0.0 },
+                BarrierKind::NoTouch => if breached(node_spot) { 0.0 } else { 1.0 },
+            }
+        })
+        .collect();
+
+    for step in (0..steps).rev() {
+        values = (0..=step)
+            .map(|j| {
+                let node_spot = spot * u.powi(j as i32) * d.powi((step - j) as i32);
+                let rolled = p * values[j + 1] + (1.0 - p) * values[j];
+                match barrier {
+                    BarrierKind::European => rolled,
+                    BarrierKind::Touch => if breached(node_spot) { 1.0 } else { rolled },
+                    BarrierKind::NoTouch => if breached(node_spot) { 0.0 } else { rolled },
+                }
+            })
+            .collect();
+    }
+
+    let yes_prob = values[0];
+    (yes_prob, 1.0 - yes_prob)
+}
+
+// === Implied volatility ===
+
+/// Search bounds for the implied-vol solvers, in annualized decimal terms.
+const IMPLIED_VOL_MIN: f64 = 1e-4;
+const IMPLIED_VOL_MAX: f64 = 20.0;
+const BISECT_ITERS: u32 = 100;
+const GOLDEN_SECTION_ITERS: u32 = 100;
+
+/// `calc_fair_value`'s YES probability as a function of vol alone, holding
+/// spot/strike/time fixed. The shared building block for the root- and
+/// maximum-finders below.
+fn yes_prob_at_vol(spot: f64, strike: f64, minutes_remaining: f64, vol: f64) -> f64 {
+    calc_fair_value(spot, strike, minutes_remaining, vol).0
+}
+
+/// Bisect for `target` on `[lo, hi]`, assuming `yes_prob_at_vol` is
+/// monotonically decreasing there. Returns `None` if `target` falls outside
+/// `[f(hi), f(lo)]`.
+fn bisect_decreasing(spot: f64, strike: f64, minutes_remaining: f64, target: f64, mut lo: f64, mut hi: f64) -> Option<f64> {
+    let price_lo = yes_prob_at_vol(spot, strike, minutes_remaining, lo);
+    let price_hi = yes_prob_at_vol(spot, strike, minutes_remaining, hi);
+    if target > price_lo || target < price_hi {
+        return None;
+    }
+    for _ in 0..BISECT_ITERS {
+        let mid = 0.5 * (lo + hi);
+        let price_mid = yes_prob_at_vol(spot, strike, minutes_remaining, mid);
+        if price_mid > target {
+            lo = mid;
+        } else {
+            hi = mid;
+        }
+    }
+    Some(0.5 * (lo + hi))
+}
+
+/// Bisect for `target` on `[lo, hi]`, assuming `yes_prob_at_vol` is
+/// monotonically increasing there. Returns `None` if `target` falls outside
+/// `[f(lo), f(hi)]`.
+fn bisect_increasing(spot: f64, strike: f64, minutes_remaining: f64, target: f64, mut lo: f64, mut hi: f64) -> Option<f64> {
+    let price_lo = yes_prob_at_vol(spot, strike, minutes_remaining, lo);
+    let price_hi = yes_prob_at_vol(spot, strike, minutes_remaining, hi);
+    if target < price_lo || target > price_hi {
+        return None;
+    }
+    for _ in 0..BISECT_ITERS {
+        let mid = 0.5 * (lo + hi);
+        let price_mid = yes_prob_at_vol(spot, strike, minutes_remaining, mid);
+        if price_mid < target {
+            lo = mid;
+        } else {
+            hi = mid;
+        }
+    }
+    Some(0.5 * (lo + hi))
+}
+
+/// Golden-section search for the vol that maximizes `yes_prob_at_vol` on
+/// `[lo, hi]`, assuming the function rises then falls (unimodal) there.
+fn golden_section_max(spot: f64, strike: f64, minutes_remaining: f64, mut lo: f64, mut hi: f64) -> f64 {
+    let phi = (5f64.sqrt() - 1.0) / 2.0; // ~0.618, the golden ratio conjugate
+    let mut c = hi - phi * (hi - lo);
+    let mut d = lo + phi * (hi - lo);
+    let mut fc = yes_prob_at_vol(spot, strike, minutes_remaining, c);
+    let mut fd = yes_prob_at_vol(spot, strike, minutes_remaining, d);
+    for _ in 0..GOLDEN_SECTION_ITERS {
+        if fc < fd {
+            lo = c;
+            c = d;
+            fc = fd;
+            d = lo + phi * (hi - lo);
+            fd = yes_prob_at_vol(spot, strike, minutes_remaining, d);
+        } else {
+            hi = d;
+            d = c;
+            fd = fc;
+            c = hi - phi * (hi - lo);
+            fc = yes_prob_at_vol(spot, strike, minutes_remaining, c);
+        }
+    }
+    0.5 * (lo + hi)
+}
+
+/// Back out every annualized vol consistent with an observed `market_yes_prob`,
+/// inverting [`calc_fair_value`] for `spot`/`strike`/`minutes_remaining` held
+/// fixed. Roots are returned ascending by vol.
+///
+/// For `spot >= strike`, N(d2) decreases monotonically from its σ→0 limit to
+/// 0 as σ→∞, so at most one root exists and a single bisection finds it. For
+/// `spot < strike`, N(d2) rises from 0 at σ→0 to a single interior maximum
+/// before falling back to 0 as σ→∞, so a target price may have zero, one
+/// (only at the maximum), or two solutions; the maximum is located by
+/// golden-section search and used to split the domain into a rising and a
+/// falling branch, each bisected independently.
+pub fn implied_vol_roots(spot: f64, strike: f64, minutes_remaining: f64, market_yes_prob: f64) -> Vec<f64> {
+    if minutes_remaining <= 0.0 || !(0.0..=1.0).contains(&market_yes_prob) {
+        return Vec::new();
+    }
+
+    if spot >= strike {
+        bisect_decreasing(spot, strike, minutes_remaining, market_yes_prob, IMPLIED_VOL_MIN, IMPLIED_VOL_MAX)
+            .into_iter()
+            .collect()
+    } else {
+        let vol_at_max = golden_section_max(spot, strike, minutes_remaining, IMPLIED_VOL_MIN, IMPLIED_VOL_MAX);
+        let max_prob = yes_prob_at_vol(spot, strike, minutes_remaining, vol_at_max);
+        if market_yes_prob > max_prob {
+            return Vec::new();
+        }
+
+        let rising = bisect_increasing(spot, strike, minutes_remaining, market_yes_prob, IMPLIED_VOL_MIN, vol_at_max);
+        let falling = bisect_decreasing(spot, strike, minutes_remaining, market_yes_prob, vol_at_max, IMPLIED_VOL_MAX);
+        rising.into_iter().chain(falling).collect()
+    }
+}
+
+/// Back out the annualized vol the market is pricing in, so a trader can
+/// compare it against [`DEFAULT_VOL`] or a realized estimate. When
+/// `spot < strike` admits two roots, the lower-vol one is returned (see
+/// [`implied_vol_roots`] for both). `None` when `market_yes_prob` is
+/// unreachable at any vol in range.
+pub fn implied_vol(spot: f64, strike: f64, minutes_remaining: f64, market_yes_prob: f64) -> Option<f64> {
+    implied_vol_roots(spot, strike, minutes_remaining, market_yes_prob)
+        .into_iter()
+        .next()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -86,6 +410,55 @@ mod tests {
         assert_eq!(no, 50);
     }
 
+    #[test]
+    fn test_zero_carry_matches_calc_fair_value() {
+        let (spot, strike, minutes, vol) = (100500.0, 100000.0, 10.0, 0.50);
+        assert_eq!(
+            calc_fair_value(spot, strike, minutes, vol),
+            calc_fair_value_with_carry(spot, strike, minutes, vol, 0.0, 0.0),
+        );
+    }
+
+    #[test]
+    fn test_positive_rate_discounts_combined_payoff() {
+        // The two legs always sum to $1 of eventual payoff, so a positive
+        // rate should discount their combined present value below $1.
+        let (spot, strike, minutes, vol) = (100500.0, 100000.0, 10.0, 0.50);
+        let (yes0, no0) = calc_fair_value_with_carry(spot, strike, minutes, vol, 0.0, 0.0);
+        let (yes_r, no_r) = calc_fair_value_with_carry(spot, strike, minutes, vol, 0.05, 0.0);
+        assert!((yes0 + no0 - 1.0).abs() < 1e-9);
+        assert!(yes_r + no_r < yes0 + no0);
+    }
+
+    #[test]
+    fn test_binomial_converges_to_closed_form() {
+        let (spot, strike, minutes, vol) = (100500.0, 100000.0, 10.0, 0.50);
+        let (closed_yes, _) = calc_fair_value(spot, strike, minutes, vol);
+        let (tree_yes, tree_no) = calc_fair_value_binomial(spot, strike, minutes, vol, 400, BarrierKind::European);
+        // Digital payoffs make CRR trees oscillate with step parity rather
+        // than converge smoothly, so the tolerance is a few tenths of a cent
+        // rather than the couple-thousandths a vanilla payoff would allow.
+        assert!((closed_yes - tree_yes).abs() < 0.005);
+        assert!((tree_yes + tree_no - 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_binomial_touch_at_least_as_likely_as_european() {
+        // Touching the strike en route is strictly easier than finishing ITM.
+        let (spot, strike, minutes, vol) = (99500.0, 100000.0, 10.0, 0.50);
+        let (european_yes, _) = calc_fair_value_binomial(spot, strike, minutes, vol, 200, BarrierKind::European);
+        let (touch_yes, _) = calc_fair_value_binomial(spot, strike, minutes, vol, 200, BarrierKind::Touch);
+        assert!(touch_yes >= european_yes);
+    }
+
+    #[test]
+    fn test_binomial_touch_and_no_touch_are_complements() {
+        let (spot, strike, minutes, vol) = (99500.0, 100000.0, 10.0, 0.50);
+        let (touch_yes, _) = calc_fair_value_binomial(spot, strike, minutes, vol, 200, BarrierKind::Touch);
+        let (no_touch_yes, _) = calc_fair_value_binomial(spot, strike, minutes, vol, 200, BarrierKind::NoTouch);
+        assert!((touch_yes + no_touch_yes - 1.0).abs() < 1e-9);
+    }
+
     #[test]
     fn test_itm_high_prob() {
         // Spot well above strike with little time
@@ -110,4 +483,66 @@ mod tests {
         assert_eq!(yes, 0);
         assert_eq!(no, 100);
     }
+
+    #[test]
+    fn test_implied_vol_round_trips_itm() {
+        let (yes_prob, _) = calc_fair_value(100500.0, 100000.0, 10.0, 0.65);
+        let vol = implied_vol(100500.0, 100000.0, 10.0, yes_prob).expect("root should exist");
+        assert!((vol - 0.65).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_implied_vol_round_trips_otm_lower_root() {
+        // spot < strike: take the lower-vol root of the two that may exist.
+        let (yes_prob, _) = calc_fair_value(99500.0, 100000.0, 10.0, 0.20);
+        let vol = implied_vol(99500.0, 100000.0, 10.0, yes_prob).expect("root should exist");
+        assert!((vol - 0.20).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_implied_vol_otm_two_roots() {
+        // A longer horizon so the interior maximum over spot < strike falls
+        // well inside the search bounds instead of at their edge.
+        let vol_at_max = golden_section_max(99500.0, 100000.0, 200.0, IMPLIED_VOL_MIN, IMPLIED_VOL_MAX);
+        let max_prob = yes_prob_at_vol(99500.0, 100000.0, 200.0, vol_at_max);
+        let roots = implied_vol_roots(99500.0, 100000.0, 200.0, max_prob * 0.9);
+        assert_eq!(roots.len(), 2);
+        assert!(roots[0] < vol_at_max && roots[1] > vol_at_max);
+    }
+
+    #[test]
+    fn test_greeks_atm_delta_is_positive_and_peaks_near_the_money() {
+        // Delta (sensitivity of YES price to spot) should be positive and
+        // largest right at the strike, where a move is most informative.
+        let atm = calc_greeks(100000.0, 100000.0, 10.0, 0.50);
+        let away = calc_greeks(100500.0, 100000.0, 10.0, 0.50);
+        assert!(atm.delta > 0.0);
+        assert!(atm.delta > away.delta);
+    }
+
+    #[test]
+    fn test_greeks_gamma_is_negative_itm() {
+        // Past the strike, delta is falling off as spot moves further away.
+        let g = calc_greeks(100500.0, 100000.0, 10.0, 0.50);
+        assert!(g.gamma < 0.0);
+    }
+
+    #[test]
+    fn test_greeks_theta_per_minute_is_day_theta_over_1440() {
+        let g = calc_greeks(100500.0, 100000.0, 10.0, 0.50);
+        assert!((g.theta_per_minute - g.theta / 1440.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_greeks_zero_at_expiry() {
+        let g = calc_greeks(100500.0, 100000.0, 0.0, 0.50);
+        assert_eq!(g, Greeks { delta: 0.0, gamma: 0.0, vega: 0.0, theta: 0.0, theta_per_minute: 0.0 });
+    }
+
+    #[test]
+    fn test_implied_vol_unreachable_is_none() {
+        let vol_at_max = golden_section_max(99500.0, 100000.0, 200.0, IMPLIED_VOL_MIN, IMPLIED_VOL_MAX);
+        let max_prob = yes_prob_at_vol(99500.0, 100000.0, 200.0, vol_at_max);
+        assert!(implied_vol(99500.0, 100000.0, 200.0, max_prob * 1.01).is_none());
+    }
 }
@@ -0,0 +1,215 @@
+//! Realized volatility estimation
+//!
+//! Feeds a rolling window of OHLC bars into one of several annualized
+//! volatility estimators, so the live pricer can track the market's actual
+//! vol regime instead of assuming `fair_value::DEFAULT_VOL` forever.
+
+use std::collections::VecDeque;
+
+/// Minutes in a year, matching `fair_value`'s minutes-to-years conversion
+/// (365.25 * 24 * 60).
+const MINUTES_PER_YEAR: f64 = 525960.0;
+
+/// RiskMetrics' standard EWMA decay factor.
+const EWMA_LAMBDA: f64 = 0.94;
+
+/// Ring-buffer capacity: enough close-to-close/Garman-Klass history for a
+/// stable estimate without unbounded memory growth.
+const DEFAULT_CAPACITY: usize = 200;
+
+/// One completed OHLC price bar at whatever resolution the caller tracks
+/// (e.g. `poly_momentum`'s candle aggregation).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Bar {
+    pub open: f64,
+    pub high: f64,
+    pub low: f64,
+    pub close: f64,
+}
+
+/// Which estimator `VolEstimator::current_annual_vol` computes from the
+/// retained window.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum VolMethod {
+    /// Std dev of close-to-close log returns, annualized by √(bars/year).
+    #[default]
+    CloseToClose,
+    /// RiskMetrics-style EWMA of squared log returns (λ≈0.94).
+    Ewma,
+    /// Garman-Klass range estimator using per-bar open/high/low/close.
+    GarmanKlass,
+}
+
+/// Rolling window of recent price bars, producing an annualized vol estimate
+/// by one of several methods so a regime change (not just `DEFAULT_VOL`)
+/// feeds `calc_fair_value`.
+#[derive(Debug, Clone)]
+pub struct VolEstimator {
+    bars: VecDeque<Bar>,
+    capacity: usize,
+    bar_minutes: f64,
+    method: VolMethod,
+    last_close: Option<f64>,
+    ewma_variance: Option<f64>,
+}
+
+impl VolEstimator {
+    /// `bar_minutes` is the width of one bar in minutes (e.g. `1.0 / 60.0`
+    /// for 1s candles), used to annualize the per-bar vol.
+    pub fn new(bar_minutes: f64, method: VolMethod) -> Self {
+        Self::with_capacity(bar_minutes, method, DEFAULT_CAPACITY)
+    }
+
+    pub fn with_capacity(bar_minutes: f64, method: VolMethod, capacity: usize) -> Self {
+        Self {
+            bars: VecDeque::with_capacity(capacity),
+            capacity,
+            bar_minutes,
+            method,
+            last_close: None,
+            ewma_variance: None,
+        }
+    }
+
+    /// Fold one completed bar into the window: updates the EWMA variance
+    /// incrementally from the close-to-close return, then retains the bar
+    /// for the close-to-close/Garman-Klass estimators, evicting the oldest
+    /// once over capacity.
+    pub fn update(&mut self, bar: Bar) {
+        if let Some(prev_close) = self.last_close {
+            if prev_close > 0.0 && bar.close > 0.0 {
+                let r2 = (bar.close / prev_close).ln().powi(2);
+                self.ewma_variance = Some(match self.ewma_variance {
+                    Some(prev) => EWMA_LAMBDA * prev + (1.0 - EWMA_LAMBDA) * r2,
+                    None => r2,
+                });
+            }
+        }
+        self.last_close = Some(bar.close);
+
+        self.bars.push_back(bar);
+        while self.bars.len() > self.capacity {
+            self.bars.pop_front();
+        }
+    }
+
+    /// Annualized vol from the configured method, or `None` with too little
+    /// history (fewer than two close-to-close returns; no return yet, i.e.
+    /// fewer than two bars, for EWMA; no bar yet for Garman-Klass).
+    pub fn current_annual_vol(&self) -> Option<f64> {
+        match self.method {
+            VolMethod::CloseToClose => self.close_to_close_vol(),
+            VolMethod::Ewma => self.ewma_vol(),
+            VolMethod::GarmanKlass => self.garman_klass_vol(),
+        }
+    }
+
+    /// √(bars per year), used to scale a per-bar vol up to annualized.
+    fn annualization_factor(&self) -> f64 {
+        (MINUTES_PER_YEAR / self.bar_minutes).sqrt()
+    }
+
+    fn close_to_close_vol(&self) -> Option<f64> {
+        let returns: Vec<f64> = self.bars.iter()
+            .zip(self.bars.iter().skip(1))
+            .filter_map(|(a, b)| (a.close > 0.0 && b.close > 0.0).then(|| (b.close / a.close).ln()))
+            .collect();
+        if returns.len() < 2 {
+            return None;
+        }
+        let mean = returns.iter().sum::<f64>() / returns.len() as f64;
+        let variance = returns.iter().map(|r| (r - mean).powi(2)).sum::<f64>() / (returns.len() - 1) as f64;
+        Some(variance.sqrt() * self.annualization_factor())
+    }
+
+    fn ewma_vol(&self) -> Option<f64> {
+        self.ewma_variance.map(|v| v.sqrt() * self.annualization_factor())
+    }
+
+    /// σ²_GK = 0.5·(ln(H/L))² − (2·ln2 − 1)·(ln(C/O))², averaged over the
+    /// window and annualized.
+    fn garman_klass_vol(&self) -> Option<f64> {
+        if self.bars.is_empty() {
+            return None;
+        }
+        let bias_term = 2.0 * std::f64::consts::LN_2 - 1.0;
+        let valid: Vec<f64> = self.bars.iter()
+            .filter(|b| b.open > 0.0 && b.high > 0.0 && b.low > 0.0 && b.close > 0.0)
+            .map(|b| {
+                let hl = (b.high / b.low).ln();
+                let co = (b.close / b.open).ln();
+                0.5 * hl.powi(2) - bias_term * co.powi(2)
+            })
+            .collect();
+        if valid.is_empty() {
+            return None;
+        }
+        let mean_variance = (valid.iter().sum::<f64>() / valid.len() as f64).max(0.0);
+        Some(mean_variance.sqrt() * self.annualization_factor())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn flat_bar(price: f64) -> Bar {
+        Bar { open: price, high: price, low: price, close: price }
+    }
+
+    #[test]
+    fn test_needs_at_least_three_bars_for_sample_variance() {
+        let mut est = VolEstimator::new(1.0, VolMethod::CloseToClose);
+        assert_eq!(est.current_annual_vol(), None);
+        est.update(flat_bar(100.0));
+        assert_eq!(est.current_annual_vol(), None);
+        est.update(flat_bar(101.0));
+        assert_eq!(est.current_annual_vol(), None); // one return isn't enough for a sample std dev
+        est.update(flat_bar(102.0));
+        assert!(est.current_annual_vol().is_some());
+    }
+
+    #[test]
+    fn test_constant_price_is_zero_vol() {
+        let mut est = VolEstimator::new(1.0, VolMethod::CloseToClose);
+        for _ in 0..10 {
+            est.update(flat_bar(100.0));
+        }
+        assert_eq!(est.current_annual_vol(), Some(0.0));
+    }
+
+    #[test]
+    fn test_ewma_tracks_a_vol_spike() {
+        let mut est = VolEstimator::new(1.0, VolMethod::Ewma);
+        for _ in 0..5 {
+            est.update(flat_bar(100.0));
+        }
+        let calm = est.current_annual_vol().unwrap();
+        est.update(Bar { open: 100.0, high: 100.0, low: 90.0, close: 90.0 });
+        let spiked = est.current_annual_vol().unwrap();
+        assert!(spiked > calm);
+    }
+
+    #[test]
+    fn test_garman_klass_zero_for_flat_bars() {
+        let mut est = VolEstimator::new(1.0, VolMethod::GarmanKlass);
+        est.update(flat_bar(100.0));
+        assert_eq!(est.current_annual_vol(), Some(0.0));
+    }
+
+    #[test]
+    fn test_garman_klass_positive_for_wide_range_bar() {
+        let mut est = VolEstimator::new(1.0, VolMethod::GarmanKlass);
+        est.update(Bar { open: 100.0, high: 110.0, low: 90.0, close: 100.0 });
+        assert!(est.current_annual_vol().unwrap() > 0.0);
+    }
+
+    #[test]
+    fn test_capacity_evicts_oldest_bar() {
+        let mut est = VolEstimator::with_capacity(1.0, VolMethod::CloseToClose, 3);
+        for price in [100.0, 101.0, 102.0, 103.0, 104.0] {
+            est.update(flat_bar(price));
+        }
+        assert_eq!(est.bars.len(), 3);
+    }
+}
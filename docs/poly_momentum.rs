@@ -20,13 +20,14 @@
 //!   POLYGON_API_KEY - Polygon.io API key for price feed
 
 use anyhow::{Context, Result};
+use async_trait::async_trait;
 use chrono::Utc;
 use futures_util::{SinkExt, StreamExt};
 use serde::{Deserialize, Serialize};
 use std::collections::{HashMap, VecDeque};
 use std::sync::Arc;
 use std::time::{Duration, Instant};
-use tokio::sync::RwLock;
+use tokio::sync::{broadcast, mpsc, RwLock};
 use tokio_tungstenite::{connect_async, tungstenite::Message};
 use tracing::{error, info, warn};
 
@@ -40,8 +41,11 @@ struct Args {
     #[arg(short, long, default_value_t = 25.0)]
     size: f64,
 
-    /// Price move threshold in basis points to trigger (default: 15 = 0.15%)
-    #[arg(short, long, default_value_t = 15)]
+    /// Legacy fixed-bps secondary filter, applied on top of the z-score
+    /// trigger (0 disables it, which is the default — the z-score trigger
+    /// adapts to each asset's own recent vol, so a fixed floor on top of it
+    /// just re-imposes a quiet-market blind spot).
+    #[arg(short, long, default_value_t = 0)]
     threshold_bps: i64,
 
     /// Lookback window in seconds for detecting moves
@@ -63,6 +67,101 @@ struct Args {
     /// Specific asset to trade (BTC, ETH, SOL, XRP) - trades all if not set
     #[arg(long)]
     asset: Option<String>,
+
+    /// Fair-value model: "linear" or "time-decay"
+    #[arg(long, default_value = "linear")]
+    model: String,
+
+    /// Cents-per-bps coefficient for the time-decay model
+    #[arg(long, default_value_t = 0.1)]
+    k: f64,
+
+    /// Extra spread cushion in bps the fill must clear on top of the model edge
+    #[arg(long, default_value_t = 0)]
+    spread_bps: i64,
+
+    /// Roll to the next contract when a market is within this many seconds of expiry
+    #[arg(long, default_value_t = 30)]
+    rollover_secs: u64,
+
+    /// Minimum z-score (in volatility units) to fire a momentum signal
+    #[arg(long, default_value_t = 3.0)]
+    z_min: f64,
+
+    /// Candle resolution for OHLC aggregation: 1s, 5s, or 1m
+    #[arg(long, default_value = "1s")]
+    candle_res: String,
+
+    /// Comma-separated price sources: polygon, kraken
+    #[arg(long, default_value = "polygon")]
+    sources: String,
+
+    /// Sources that must confirm a move before trading
+    #[arg(long, default_value_t = 1)]
+    min_confirmations: usize,
+
+    /// SQLite path for trade/signal/PnL persistence (disabled if unset)
+    #[arg(long)]
+    db: Option<String>,
+
+    /// Webhook/Telegram URL to push fill and disconnect notifications to
+    #[arg(long)]
+    webhook_url: Option<String>,
+
+    /// Postgres URL to persist book mid-price OHLC candles to (disabled if unset)
+    #[arg(long)]
+    pg_url: Option<String>,
+
+    /// Address to serve Prometheus-style metrics on, e.g. 127.0.0.1:9898 (disabled if unset)
+    #[arg(long)]
+    metrics_addr: Option<String>,
+
+    /// Address to serve the fills broadcast WebSocket on, e.g. 127.0.0.1:9899 (disabled if unset)
+    #[arg(long)]
+    fills_addr: Option<String>,
+
+    /// Unrealized edge (in cents) at which an open position takes profit
+    #[arg(long, default_value_t = 5)]
+    take_profit: i64,
+
+    /// Unrealized loss (in cents) at which an open position stops out
+    #[arg(long, default_value_t = 5)]
+    stop_loss: i64,
+
+    /// Maximum time to hold a position before closing it regardless of price (seconds)
+    #[arg(long, default_value_t = 600)]
+    max_hold_secs: u64,
+
+    /// Maximum contracts to hold per token before new buys are skipped
+    #[arg(long, default_value_t = 500.0)]
+    max_inventory: f64,
+
+    /// Minimum traded volume (contracts) in the recent trade tape required to
+    /// confirm a signal before buying; 0 disables the check
+    #[arg(long, default_value_t = 0.0)]
+    min_flow_volume: f64,
+
+    /// How long a feed (Polymarket WS or a price source) must stay down
+    /// before it pages via the webhook — debounces the brief reconnect
+    /// blips every socket has from real outages
+    #[arg(long, default_value_t = 30)]
+    disconnect_alert_secs: u64,
+
+    /// Append every inbound market-data/price-feed event to this file for
+    /// later backtest replay (disabled if unset)
+    #[arg(long)]
+    record_to: Option<String>,
+
+    /// Replay a file captured via --record-to instead of connecting to live
+    /// feeds, exercising the same signal/decision code in dry-run and
+    /// reporting aggregate stats at the end
+    #[arg(long)]
+    replay_from: Option<String>,
+
+    /// Speed multiplier for replay relative to the original capture timing;
+    /// 0 replays as fast as possible
+    #[arg(long, default_value_t = 1.0)]
+    replay_speed: f64,
 }
 
 const POLYMARKET_WS_URL: &str = "wss://ws-subscriptions-clob.polymarket.com/ws/market";
@@ -76,30 +175,207 @@ struct PriceTick {
     timestamp: Instant,
 }
 
+/// Candle resolution for OHLC aggregation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+enum Resolution {
+    #[default]
+    S1,
+    S5,
+    M1,
+}
+
+impl Resolution {
+    /// Bucket width in seconds.
+    fn secs(self) -> u64 {
+        match self {
+            Resolution::S1 => 1,
+            Resolution::S5 => 5,
+            Resolution::M1 => 60,
+        }
+    }
+
+    fn parse(s: &str) -> Option<Self> {
+        match s {
+            "1s" => Some(Resolution::S1),
+            "5s" => Some(Resolution::S5),
+            "1m" => Some(Resolution::M1),
+            _ => None,
+        }
+    }
+}
+
+/// A completed or in-progress OHLC bar.
+#[derive(Debug, Clone, Copy)]
+struct Candle {
+    start: Instant,
+    open: f64,
+    high: f64,
+    low: f64,
+    close: f64,
+}
+
+/// Reversible Welford accumulator over a rolling window of log-returns, giving
+/// O(1) running mean and variance as returns are added and evicted.
+#[derive(Debug, Default)]
+struct Welford {
+    n: u64,
+    mean: f64,
+    m2: f64,
+}
+
+impl Welford {
+    fn push(&mut self, x: f64) {
+        self.n += 1;
+        let delta = x - self.mean;
+        self.mean += delta / self.n as f64;
+        self.m2 += delta * (x - self.mean);
+    }
+
+    fn remove(&mut self, x: f64) {
+        if self.n <= 1 {
+            *self = Welford::default();
+            return;
+        }
+        let mean_old = self.mean;
+        self.mean = (self.n as f64 * self.mean - x) / (self.n as f64 - 1.0);
+        self.m2 -= (x - mean_old) * (x - self.mean);
+        self.n -= 1;
+    }
+
+    /// Sample standard deviation, or `None` with fewer than two samples.
+    fn std_dev(&self) -> Option<f64> {
+        if self.n < 2 {
+            return None;
+        }
+        Some((self.m2 / (self.n as f64 - 1.0)).max(0.0).sqrt())
+    }
+}
+
+/// Minimum completed returns required before emitting z-score signals.
+const RETURN_WINDOW: usize = 30;
+
 /// Price history for momentum detection
 #[derive(Debug, Default)]
 struct PriceHistory {
     ticks: VecDeque<PriceTick>,
     last_price: Option<f64>,
+    // OHLC aggregation
+    resolution: Resolution,
+    current_candle: Option<Candle>,
+    candles: VecDeque<Candle>,
+    // Rolling log-returns between completed candles
+    returns: VecDeque<f64>,
+    ret_stats: Welford,
+    last_candle_close: Option<f64>,
 }
 
 impl PriceHistory {
     fn add_tick(&mut self, price: f64) {
+        let now = Instant::now();
         let tick = PriceTick {
             price,
-            timestamp: Instant::now(),
+            timestamp: now,
         };
         self.ticks.push_back(tick);
         self.last_price = Some(price);
 
+        self.roll_candle(price, now);
+
         // Keep only last 60 seconds of ticks
-        let cutoff = Instant::now() - Duration::from_secs(60);
+        let cutoff = now - Duration::from_secs(60);
         while self.ticks.front().map(|t| t.timestamp < cutoff).unwrap_or(false) {
             self.ticks.pop_front();
         }
+        // Evict completed candles older than the same 60s retention
+        while self.candles.front().map(|c| c.start < cutoff).unwrap_or(false) {
+            self.candles.pop_front();
+        }
+    }
+
+    /// Fold a tick into the current OHLC bar, completing the bar (and recording
+    /// its log-return) when the bucket boundary is crossed.
+    fn roll_candle(&mut self, price: f64, now: Instant) {
+        let bucket = Duration::from_secs(self.resolution.secs());
+        match &mut self.current_candle {
+            Some(c) if now.duration_since(c.start) < bucket => {
+                c.high = c.high.max(price);
+                c.low = c.low.min(price);
+                c.close = price;
+            }
+            _ => {
+                // Complete the previous bar, if any, and start a fresh one.
+                if let Some(done) = self.current_candle.take() {
+                    self.complete_candle(done);
+                }
+                self.current_candle = Some(Candle {
+                    start: now,
+                    open: price,
+                    high: price,
+                    low: price,
+                    close: price,
+                });
+            }
+        }
+    }
+
+    /// Record a completed candle and the log-return from the prior close,
+    /// maintaining the rolling return window and its Welford stats.
+    fn complete_candle(&mut self, candle: Candle) {
+        if let Some(prev_close) = self.last_candle_close {
+            if prev_close > 0.0 && candle.close > 0.0 {
+                let r = (candle.close / prev_close).ln();
+                self.returns.push_back(r);
+                self.ret_stats.push(r);
+                while self.returns.len() > RETURN_WINDOW {
+                    if let Some(old) = self.returns.pop_front() {
+                        self.ret_stats.remove(old);
+                    }
+                }
+            }
+        }
+        self.last_candle_close = Some(candle.close);
+        self.candles.push_back(candle);
+    }
+
+    /// Per-candle log-return volatility, once the warm-up window is full.
+    fn return_volatility(&self) -> Option<f64> {
+        if self.returns.len() < RETURN_WINDOW {
+            return None;
+        }
+        self.ret_stats.std_dev()
+    }
+
+    /// Volatility-normalized momentum trigger.
+    ///
+    /// Fires when the window log-return `r_win = ln(p_now / p_window_start)`
+    /// exceeds `z_min * sigma * sqrt(window_candles)`, where `sigma` is the
+    /// per-candle return std dev. Returns `(direction, move_bps)` or `None`
+    /// during warm-up, when `sigma == 0`, or below threshold.
+    fn zscore_trigger(&self, window_secs: u64, z_min: f64) -> Option<(Direction, i64)> {
+        let sigma = self.return_volatility()?;
+        if sigma == 0.0 {
+            return None;
+        }
+        let window_candles = (window_secs / self.resolution.secs()).max(1);
+        let threshold = z_min * sigma * (window_candles as f64).sqrt();
+
+        let p_now = self.last_price?;
+        let p_start = self.price_at(window_secs)?;
+        if p_start <= 0.0 {
+            return None;
+        }
+        let r_win = (p_now / p_start).ln();
+        if r_win.abs() < threshold {
+            return None;
+        }
+
+        let direction = if r_win > 0.0 { Direction::Up } else { Direction::Down };
+        let move_bps = (r_win * 10000.0).round() as i64;
+        Some((direction, move_bps))
     }
 
     /// Calculate price change over window in basis points
+    #[allow(dead_code)]
     fn price_change_bps(&self, window_secs: u64) -> Option<i64> {
         let cutoff = Instant::now() - Duration::from_secs(window_secs);
 
@@ -132,20 +408,121 @@ struct Market {
     no_token: String,
     asset: String,
     expiry_minutes: Option<f64>,
-    // Orderbook
+    // Best bid/ask, cached from `yes_book`/`no_book` after each apply so the
+    // hot paths (fair value, candle mid) don't have to walk the ladder.
     yes_ask: Option<i64>,
     yes_bid: Option<i64>,
     no_ask: Option<i64>,
     no_bid: Option<i64>,
+    // Full price ladders with delta sequencing, per token.
+    yes_book: OrderBookSide,
+    no_book: OrderBookSide,
+    // Rolling window of executed prints per token, used to confirm momentum
+    // signals against real flow rather than quote flicker.
+    yes_tape: TradeTape,
+    no_tape: TradeTape,
     // Trading state
     last_trade_time: Option<Instant>,
+    // When this market was discovered, used to age `expiry_minutes` for rollover
+    discovered_at: Instant,
+}
+
+impl Market {
+    /// Seconds remaining until expiry, aging the snapshotted `expiry_minutes`
+    /// by the time elapsed since discovery. `None` means we have no expiry
+    /// (treat as already expired).
+    fn seconds_to_expiry(&self) -> Option<f64> {
+        self.expiry_minutes
+            .map(|mins| mins * 60.0 - self.discovered_at.elapsed().as_secs_f64())
+    }
 }
 
 /// Global state
 struct State {
     markets: HashMap<String, Market>,
-    price_history: HashMap<String, PriceHistory>, // asset -> history
+    // (source, asset) -> price history, so each venue is tracked independently
+    price_history: HashMap<(String, String), PriceHistory>,
     pending_signals: Vec<MomentumSignal>,
+    // Per-asset recent per-source triggers, used for cross-source confirmation
+    confirmations: HashMap<String, Vec<SourceTrigger>>,
+    // Set when markets roll over so the WS loop resubscribes to the new tokens
+    subscription_dirty: bool,
+    // Candle resolution applied to newly-created histories
+    resolution: Resolution,
+    // Internal event bus decoupling signal production from consumers/notifiers
+    events: broadcast::Sender<BotEvent>,
+    // Open inventory per token, built from live buy_fak fills and closed out
+    // by the exit evaluator
+    positions: HashMap<String, Position>,
+}
+
+/// An open inventory position in one token, opened and averaged from live
+/// fills. Closed out entirely (never partially) by the exit evaluator.
+#[derive(Debug, Clone)]
+struct Position {
+    token: String,
+    contracts: f64,
+    avg_entry_cents: i64,
+    opened_at: Instant,
+}
+
+/// Shared take-profit/stop-loss/max-hold decision, used by both the live
+/// exit evaluator and the backtest replay so they agree on when a position
+/// closes.
+fn evaluate_exit(
+    edge_cents: i64,
+    held_for: Duration,
+    take_profit_cents: i64,
+    stop_loss_cents: i64,
+    max_hold: Duration,
+) -> Option<&'static str> {
+    if edge_cents >= take_profit_cents {
+        Some("take_profit")
+    } else if edge_cents <= -stop_loss_cents {
+        Some("stop_loss")
+    } else if held_for >= max_hold {
+        Some("max_hold")
+    } else {
+        None
+    }
+}
+
+/// Internal event bus payload, fanned out over a `tokio::sync::broadcast`
+/// channel to decouple signal production from consumers (notifiers, dashboards).
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "type")]
+enum BotEvent {
+    SignalDetected { asset: String, direction: Direction, move_bps: i64 },
+    TradeSubmitted { asset: String, side: String, price_cents: i64, size: f64 },
+    TradeFilled {
+        market_id: String,
+        asset: String,
+        side: String,
+        filled_size: f64,
+        fill_cost: f64,
+        order_id: String,
+    },
+    TradeSkipped { asset: String, reason: String },
+    PositionClosed {
+        token: String,
+        asset: String,
+        side: String,
+        contracts: f64,
+        entry_cents: i64,
+        exit_cents: i64,
+        reason: String,
+    },
+    MarketRolledOver { asset: String, new_market_id: String },
+    FeedDisconnected { source: String },
+}
+
+/// A momentum trigger observed on one source, awaiting cross-source confirmation.
+#[derive(Debug, Clone)]
+struct SourceTrigger {
+    source: String,
+    direction: Direction,
+    move_bps: i64,
+    at: Instant,
 }
 
 #[derive(Debug, Clone)]
@@ -156,24 +533,253 @@ struct MomentumSignal {
     triggered_at: Instant,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
 enum Direction {
     Up,
     Down,
 }
 
+// === Fair-value models ===
+
+/// Estimates the fair value (in cents, 0-100) of the side a momentum signal
+/// would buy, given the current orderbook and time to expiry.
+///
+/// Factored out of the signal loop so the edge logic is testable and the model
+/// can be swapped from the CLI rather than hardcoded.
+trait FairValueModel {
+    /// Fair value in cents for the leg implied by `signal.direction`.
+    fn fair_cents(&self, signal: &MomentumSignal, market: &Market, expiry_minutes: Option<f64>) -> i64;
+}
+
+/// The original inline rule: each ~10bps of move is worth ~1¢ of edge over 50¢.
+struct LinearMoveModel;
+
+impl FairValueModel for LinearMoveModel {
+    fn fair_cents(&self, signal: &MomentumSignal, _market: &Market, _expiry_minutes: Option<f64>) -> i64 {
+        (50 + signal.move_bps.abs() / 10).clamp(1, 99)
+    }
+}
+
+/// Time-decay-aware fair value for the 15-minute up/down markets.
+///
+/// Because a "YES (up)" contract converges to 0 or 1 at expiry, a given bps
+/// move carries more information as expiry approaches:
+/// `fair = 50 + k * |move_bps| * sqrt(T0 / max(T_remaining, eps))`, clamped to
+/// `[1, 99]`. Early-window signals stay conservative; late-window signals grow
+/// aggressive.
+struct TimeDecayModel {
+    /// Cents of edge per basis point, before the time-decay multiplier.
+    k: f64,
+}
+
+impl TimeDecayModel {
+    /// Nominal series length in minutes.
+    const T0: f64 = 15.0;
+    /// Floor on remaining time to avoid a blow-up at expiry.
+    const EPS: f64 = 0.25;
+}
+
+impl FairValueModel for TimeDecayModel {
+    fn fair_cents(&self, signal: &MomentumSignal, _market: &Market, expiry_minutes: Option<f64>) -> i64 {
+        let t_remaining = expiry_minutes.unwrap_or(Self::T0).max(Self::EPS);
+        let decay = (Self::T0 / t_remaining).sqrt();
+        let fair = 50.0 + self.k * signal.move_bps.abs() as f64 * decay;
+        (fair.round() as i64).clamp(1, 99)
+    }
+}
+
+/// Convert a bps spread cushion to cents, rounding to the nearest cent
+/// instead of truncating — `spread_bps` under 100 (e.g. 50 = half a cent)
+/// would otherwise floor to a 0¢ no-op cushion.
+fn bps_to_cents_rounded(bps: i64) -> i64 {
+    (bps as f64 / 100.0).round() as i64
+}
+
+/// Select a fair-value model by CLI name.
+fn make_fair_value_model(name: &str, k: f64) -> Result<Box<dyn FairValueModel + Send + Sync>> {
+    match name {
+        "linear" => Ok(Box::new(LinearMoveModel)),
+        "time-decay" => Ok(Box::new(TimeDecayModel { k })),
+        other => anyhow::bail!("unknown fair-value model '{}' (expected: linear, time-decay)", other),
+    }
+}
+
 impl State {
     fn new() -> Self {
-        let mut price_history = HashMap::new();
-        for asset in ["BTC", "ETH", "SOL", "XRP"] {
-            price_history.insert(asset.to_string(), PriceHistory::default());
-        }
-
+        let (events, _) = broadcast::channel(256);
         Self {
             markets: HashMap::new(),
-            price_history,
+            price_history: HashMap::new(),
             pending_signals: Vec::new(),
+            confirmations: HashMap::new(),
+            subscription_dirty: false,
+            resolution: Resolution::default(),
+            events,
+            positions: HashMap::new(),
+        }
+    }
+
+    /// Publish an event on the internal bus. Errors (no subscribers) are
+    /// intentionally ignored — the bus is fire-and-forget.
+    fn publish(&self, event: BotEvent) {
+        let _ = self.events.send(event);
+    }
+
+    /// Mutable handle to the `(source, asset)` history, creating it (with the
+    /// configured resolution) on first use.
+    fn history_mut(&mut self, source: &str, asset: &str) -> &mut PriceHistory {
+        let resolution = self.resolution;
+        self.price_history
+            .entry((source.to_string(), asset.to_string()))
+            .or_insert_with(|| PriceHistory {
+                resolution,
+                ..PriceHistory::default()
+            })
+    }
+
+    /// Record a per-source trigger and return `true` once at least
+    /// `min_confirmations` distinct sources agree on the direction within the
+    /// lookback window. On confirmation the asset's triggers are cleared.
+    fn confirm_trigger(
+        &mut self,
+        asset: &str,
+        trigger: SourceTrigger,
+        window: Duration,
+        min_confirmations: usize,
+    ) -> bool {
+        let direction = trigger.direction;
+        let entry = self.confirmations.entry(asset.to_string()).or_default();
+        entry.retain(|t| t.at.elapsed() < window);
+        // Replace any prior trigger from the same source.
+        entry.retain(|t| t.source != trigger.source);
+        entry.push(trigger);
+
+        let mut sources: Vec<&str> = entry
+            .iter()
+            .filter(|t| t.direction == direction)
+            .map(|t| t.source.as_str())
+            .collect();
+        sources.sort_unstable();
+        sources.dedup();
+
+        if sources.len() >= min_confirmations {
+            self.confirmations.remove(asset);
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Apply one inbound market-data event (book snapshot, delta, or trade
+    /// print) to the matching market's ladder, tape, and cached best bid/ask.
+    /// Shared by the live feed and the backtest replay driver so both run
+    /// book/tape updates through identical code. Returns the affected
+    /// market's id so callers that fold candles can look it up again.
+    fn apply_book_event(&mut self, event: BookEvent) -> Option<String> {
+        let asset_id = match &event {
+            BookEvent::Book(snap) => snap.asset_id.clone(),
+            BookEvent::PriceChange(delta) => delta.asset_id.clone(),
+            BookEvent::LastTradePrice(print) => print.asset_id.clone(),
+        };
+        let market = self.markets.values_mut()
+            .find(|m| m.yes_token == asset_id || m.no_token == asset_id)?;
+        let is_yes = asset_id == market.yes_token;
+
+        match event {
+            BookEvent::Book(snap) => {
+                let side = if is_yes { &mut market.yes_book } else { &mut market.no_book };
+                side.apply_snapshot(snap.seq, &snap.bids, &snap.asks);
+            }
+            BookEvent::PriceChange(delta) => {
+                let side = if is_yes { &mut market.yes_book } else { &mut market.no_book };
+                side.apply_delta(delta);
+            }
+            BookEvent::LastTradePrice(print) => {
+                let price_cents = parse_price_cents(&print.price);
+                let size = print.size.parse::<f64>().unwrap_or(0.0);
+                let remaining = print.remaining_size.parse::<f64>().unwrap_or(0.0);
+
+                let tape = if is_yes { &mut market.yes_tape } else { &mut market.no_tape };
+                tape.record(Deal { price_cents, size, side: print.side, at: Instant::now() });
+
+                let book = if is_yes { &mut market.yes_book } else { &mut market.no_book };
+                book.apply_trade(print.side, price_cents, remaining);
+            }
+        }
+
+        // Recompute best bid/ask from the ladder after every apply.
+        let side = if is_yes { &market.yes_book } else { &market.no_book };
+        let (best_bid, best_ask) = (side.best_bid_cents(), side.best_ask_cents());
+        if is_yes {
+            market.yes_bid = best_bid;
+            market.yes_ask = best_ask;
+        } else {
+            market.no_bid = best_bid;
+            market.no_ask = best_ask;
+        }
+
+        Some(market.condition_id.clone())
+    }
+
+    /// Feed one price-feed tick through history tracking, z-score detection,
+    /// and cross-source confirmation, pushing a `MomentumSignal` when enough
+    /// sources agree. Shared by the live feed and the backtest replay driver.
+    ///
+    /// The z-score trigger is the sole signal gate: it adapts to each
+    /// asset's recent vol, so a quiet market still fires on a move that's
+    /// large *for it*. `threshold_bps` is only an optional legacy secondary
+    /// filter on top of that (0 disables it, the default).
+    fn ingest_price_update(
+        &mut self,
+        update: &PriceUpdate,
+        window_secs: u64,
+        z_min: f64,
+        threshold_bps: i64,
+        min_confirmations: usize,
+    ) {
+        let asset = update.asset.clone();
+
+        let (direction, move_bps) = {
+            let history = self.history_mut(&update.source, &asset);
+            history.add_tick(update.price);
+            match history.zscore_trigger(window_secs, z_min) {
+                Some(trigger) => trigger,
+                None => return,
+            }
+        };
+        if threshold_bps > 0 && move_bps.abs() < threshold_bps {
+            return;
         }
+
+        let trigger = SourceTrigger {
+            source: update.source.clone(),
+            direction,
+            move_bps,
+            at: update.at,
+        };
+
+        let window = Duration::from_secs(window_secs);
+        if !self.confirm_trigger(&asset, trigger, window, min_confirmations) {
+            return;
+        }
+        if self.pending_signals.iter().any(|sig| sig.asset == asset) {
+            return;
+        }
+
+        warn!("[SIGNAL] {} {:?} {}bps z>={:.1} confirmed by >={} sources (${:.2} over {}s)",
+              asset, direction, move_bps.abs(), z_min, min_confirmations, update.price, window_secs);
+
+        self.publish(BotEvent::SignalDetected {
+            asset: asset.clone(),
+            direction,
+            move_bps,
+        });
+        self.pending_signals.push(MomentumSignal {
+            asset,
+            direction,
+            move_bps,
+            triggered_at: Instant::now(),
+        });
     }
 }
 
@@ -310,7 +916,12 @@ async fn discover_markets(asset_filter: Option<&str>) -> Result<Vec<Market>> {
                     yes_bid: None,
                     no_ask: None,
                     no_bid: None,
+                    yes_book: OrderBookSide::default(),
+                    no_book: OrderBookSide::default(),
+                    yes_tape: TradeTape::default(),
+                    no_tape: TradeTape::default(),
                     last_trade_time: None,
+                    discovered_at: Instant::now(),
                 });
             }
         }
@@ -329,7 +940,32 @@ async fn discover_markets(asset_filter: Option<&str>) -> Result<Vec<Market>> {
     Ok(best_per_asset.into_values().collect())
 }
 
-// === Polygon Price Feed ===
+// === Price feeds ===
+
+/// A single price observation from a venue.
+#[derive(Debug, Clone)]
+struct PriceUpdate {
+    source: String,
+    asset: String,
+    price: f64,
+    at: Instant,
+}
+
+/// A streaming spot-price venue. Implementations connect to their websocket and
+/// push `(asset, price, Instant)` observations onto the channel until the
+/// connection drops; the caller's reconnect loop handles re-connection.
+#[async_trait]
+trait PriceSource: Send + Sync {
+    fn name(&self) -> &'static str;
+
+    /// Stream price updates until the connection ends or errors.
+    async fn stream(&self, tx: mpsc::UnboundedSender<(String, f64, Instant)>) -> Result<()>;
+}
+
+/// Polygon.io crypto trades feed (`XT.*`).
+struct PolygonSource {
+    api_key: String,
+}
 
 #[derive(Deserialize, Debug)]
 struct PolygonMessage {
@@ -338,34 +974,22 @@ struct PolygonMessage {
     p: Option<f64>,
 }
 
-/// Run Polygon price feed and detect momentum signals
-async fn run_price_feed(
-    state: Arc<RwLock<State>>,
-    api_key: &str,
-    threshold_bps: i64,
-    window_secs: u64,
-) {
-    loop {
-        info!("[POLYGON] Connecting to price feed...");
-
-        let url = format!("{}?apiKey={}", POLYGON_WS_URL, api_key);
-        let ws = match connect_async(&url).await {
-            Ok((ws, _)) => ws,
-            Err(e) => {
-                error!("[POLYGON] Connect failed: {}", e);
-                tokio::time::sleep(Duration::from_secs(5)).await;
-                continue;
-            }
-        };
+#[async_trait]
+impl PriceSource for PolygonSource {
+    fn name(&self) -> &'static str {
+        "polygon"
+    }
 
+    async fn stream(&self, tx: mpsc::UnboundedSender<(String, f64, Instant)>) -> Result<()> {
+        let url = format!("{}?apiKey={}", POLYGON_WS_URL, self.api_key);
+        let (ws, _) = connect_async(&url).await.context("polygon connect failed")?;
         let (mut write, mut read) = ws.split();
 
-        // Subscribe to crypto pairs
         let sub = serde_json::json!({
             "action": "subscribe",
             "params": "XT.BTC-USD,XT.ETH-USD,XT.SOL-USD,XT.XRP-USD"
         });
-        let _ = write.send(Message::Text(sub.to_string())).await;
+        write.send(Message::Text(sub.to_string())).await?;
         info!("[POLYGON] Subscribed to BTC, ETH, SOL, XRP");
 
         while let Some(msg) = read.next().await {
@@ -376,56 +1000,10 @@ async fn run_price_feed(
                             if m.ev.as_deref() != Some("XT") {
                                 continue;
                             }
-
-                            let Some(pair) = m.pair.as_ref() else { continue };
-                            let Some(price) = m.p else { continue };
-
-                            let asset = match pair.as_str() {
-                                "BTC-USD" => "BTC",
-                                "ETH-USD" => "ETH",
-                                "SOL-USD" => "SOL",
-                                "XRP-USD" => "XRP",
-                                _ => continue,
-                            };
-
-                            let mut s = state.write().await;
-
-                            // Add tick to history
-                            if let Some(history) = s.price_history.get_mut(asset) {
-                                let old_price = history.last_price;
-                                history.add_tick(price);
-
-                                // Check for momentum signal
-                                if let Some(change_bps) = history.price_change_bps(window_secs) {
-                                    if change_bps.abs() >= threshold_bps {
-                                        let direction = if change_bps > 0 {
-                                            Direction::Up
-                                        } else {
-                                            Direction::Down
-                                        };
-
-                                        // Only signal if this is a new move (price crossed threshold)
-                                        let should_signal = old_price.map(|op| {
-                                            let old_change = ((price - op) / op * 10000.0).round() as i64;
-                                            old_change.abs() < threshold_bps
-                                        }).unwrap_or(false);
-
-                                        if should_signal || s.pending_signals.iter()
-                                            .filter(|sig| sig.asset == asset)
-                                            .count() == 0
-                                        {
-                                            warn!("[SIGNAL] {} {:?} {}bps (${:.2} over {}s window)",
-                                                  asset, direction, change_bps.abs(), price, window_secs);
-
-                                            s.pending_signals.push(MomentumSignal {
-                                                asset: asset.to_string(),
-                                                direction,
-                                                move_bps: change_bps,
-                                                triggered_at: Instant::now(),
-                                            });
-                                        }
-                                    }
-                                }
+                            let (Some(pair), Some(price)) = (m.pair.as_ref(), m.p) else { continue };
+                            let Some(asset) = normalize_pair(pair) else { continue };
+                            if tx.send((asset.to_string(), price, Instant::now())).is_err() {
+                                return Ok(());
                             }
                         }
                     }
@@ -433,50 +1011,1555 @@ async fn run_price_feed(
                 Ok(Message::Ping(data)) => {
                     let _ = write.send(Message::Pong(data)).await;
                 }
-                Err(e) => {
-                    error!("[POLYGON] WebSocket error: {}", e);
-                    break;
+                Ok(Message::Close(_)) | Err(_) => break,
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Kraken public spot ticker feed. Kraken sends ticker updates as tagged JSON
+/// arrays: `[channelID, {"c":["<last>", ...], ...}, "ticker", "XBT/USD"]`.
+struct KrakenSource;
+
+const KRAKEN_WS_URL: &str = "wss://ws.kraken.com";
+const KRAKEN_PAIRS: &[&str] = &["XBT/USD", "ETH/USD", "SOL/USD", "XRP/USD"];
+
+#[async_trait]
+impl PriceSource for KrakenSource {
+    fn name(&self) -> &'static str {
+        "kraken"
+    }
+
+    async fn stream(&self, tx: mpsc::UnboundedSender<(String, f64, Instant)>) -> Result<()> {
+        let (ws, _) = connect_async(KRAKEN_WS_URL).await.context("kraken connect failed")?;
+        let (mut write, mut read) = ws.split();
+
+        let sub = serde_json::json!({
+            "event": "subscribe",
+            "pair": KRAKEN_PAIRS,
+            "subscription": { "name": "ticker" }
+        });
+        write.send(Message::Text(sub.to_string())).await?;
+        info!("[KRAKEN] Subscribed to BTC, ETH, SOL, XRP");
+
+        while let Some(msg) = read.next().await {
+            match msg {
+                Ok(Message::Text(text)) => {
+                    let Ok(value) = serde_json::from_str::<serde_json::Value>(&text) else { continue };
+                    // Ticker payloads are arrays; status messages are objects.
+                    let Some(arr) = value.as_array() else { continue };
+                    if arr.len() < 4 || arr[2].as_str() != Some("ticker") {
+                        continue;
+                    }
+                    let Some(pair) = arr[3].as_str().and_then(normalize_pair) else { continue };
+                    let price = arr[1]
+                        .get("c")
+                        .and_then(|c| c.get(0))
+                        .and_then(|p| p.as_str())
+                        .and_then(|p| p.parse::<f64>().ok());
+                    let Some(price) = price else { continue };
+                    if tx.send((pair.to_string(), price, Instant::now())).is_err() {
+                        return Ok(());
+                    }
+                }
+                Ok(Message::Ping(data)) => {
+                    let _ = write.send(Message::Pong(data)).await;
                 }
+                Ok(Message::Close(_)) | Err(_) => break,
                 _ => {}
             }
         }
+        Ok(())
+    }
+}
 
-        warn!("[POLYGON] Disconnected, reconnecting in 2s...");
-        tokio::time::sleep(Duration::from_secs(2)).await;
+/// Map a venue's pair symbol to our canonical asset ticker.
+fn normalize_pair(pair: &str) -> Option<&'static str> {
+    match pair {
+        "BTC-USD" | "XBT/USD" | "BTC/USD" => Some("BTC"),
+        "ETH-USD" | "ETH/USD" => Some("ETH"),
+        "SOL-USD" | "SOL/USD" => Some("SOL"),
+        "XRP-USD" | "XRP/USD" => Some("XRP"),
+        _ => None,
     }
 }
 
-// === Polymarket WebSocket ===
+/// Run all price sources concurrently and promote a `MomentumSignal` only when
+/// the move is confirmed on at least `min_confirmations` of the sources within
+/// the lookback window. Each source writes into its own `(source, asset)`
+/// history so a single bad tick or thin-venue spike cannot trigger alone.
+async fn run_price_feeds(
+    state: Arc<RwLock<State>>,
+    sources: Vec<Box<dyn PriceSource>>,
+    threshold_bps: i64,
+    window_secs: u64,
+    z_min: f64,
+    min_confirmations: usize,
+    recorder: Option<Arc<Recorder>>,
+    disconnect_alert_secs: u64,
+) {
+    let (tx, mut rx) = mpsc::unbounded_channel::<PriceUpdate>();
+    let events = state.read().await.events.clone();
+
+    // Spawn each source behind its own reconnect loop.
+    for source in sources {
+        let tx = tx.clone();
+        let events = events.clone();
+        tokio::spawn(async move {
+            let name = source.name();
+            let mut outage = DisconnectDebouncer::new(disconnect_alert_secs);
+            loop {
+                let (fwd_tx, mut fwd_rx) = mpsc::unbounded_channel::<(String, f64, Instant)>();
+                let forward_tx = tx.clone();
+                let forwarder = tokio::spawn(async move {
+                    while let Some((asset, price, at)) = fwd_rx.recv().await {
+                        let _ = forward_tx.send(PriceUpdate {
+                            source: name.to_string(),
+                            asset,
+                            price,
+                            at,
+                        });
+                    }
+                });
 
-#[derive(Deserialize, Debug)]
-struct BookSnapshot {
-    asset_id: String,
-    bids: Vec<PriceLevel>,
-    asks: Vec<PriceLevel>,
+                info!("[{}] Connecting to price feed...", name.to_uppercase());
+                let connected_at = Instant::now();
+                if let Err(e) = source.stream(fwd_tx).await {
+                    error!("[{}] Feed error: {}", name.to_uppercase(), e);
+                }
+                forwarder.abort();
+                if outage.on_disconnect(connected_at.elapsed()) {
+                    let _ = events.send(BotEvent::FeedDisconnected { source: name.to_string() });
+                }
+                warn!("[{}] Disconnected, reconnecting in 3s...", name.to_uppercase());
+                tokio::time::sleep(Duration::from_secs(3)).await;
+            }
+        });
+    }
+    drop(tx);
+
+    while let Some(update) = rx.recv().await {
+        if let Some(recorder) = &recorder {
+            recorder.record(CapturedEvent::Price {
+                source: update.source.clone(),
+                asset: update.asset.clone(),
+                price: update.price,
+            });
+        }
+
+        let mut s = state.write().await;
+        s.ingest_price_update(&update, window_secs, z_min, threshold_bps, min_confirmations);
+    }
 }
 
-#[derive(Deserialize, Debug)]
-struct PriceLevel {
-    price: String,
-    size: String,
+// === Persistence ===
+
+/// What happened to a signal at decision time.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum TradeOutcome {
+    /// Skipped before any order was placed.
+    Skipped,
+    /// Would-have trade logged in dry-run mode.
+    DryRun,
+    /// Order submitted to the exchange.
+    Submitted,
+    /// Order filled (recorded from the async fill callback).
+    Filled,
+    /// Position closed (take-profit/stop-loss/max-hold exit), with realized
+    /// PnL known.
+    Closed,
 }
 
-#[derive(Serialize)]
-struct SubscribeCmd {
-    assets_ids: Vec<String>,
-    #[serde(rename = "type")]
-    sub_type: &'static str,
+impl TradeOutcome {
+    fn as_str(self) -> &'static str {
+        match self {
+            TradeOutcome::Skipped => "skipped",
+            TradeOutcome::DryRun => "dry_run",
+            TradeOutcome::Submitted => "submitted",
+            TradeOutcome::Filled => "filled",
+            TradeOutcome::Closed => "closed",
+        }
+    }
 }
 
-fn parse_price_cents(s: &str) -> i64 {
-    s.parse::<f64>()
-        .map(|p| (p * 100.0).round() as i64)
-        .unwrap_or(0)
+/// One durable record of a signal and the decision it produced, including the
+/// orderbook snapshot at decision time and (once known) the fill and PnL.
+#[derive(Debug, Clone)]
+struct TradeRecord {
+    market_id: String,
+    asset: String,
+    direction: Direction,
+    move_bps: i64,
+    // Orderbook snapshot at decision time
+    yes_ask: Option<i64>,
+    yes_bid: Option<i64>,
+    no_ask: Option<i64>,
+    no_bid: Option<i64>,
+    outcome: TradeOutcome,
+    reason: Option<String>,
+    side: Option<String>,
+    price_cents: Option<i64>,
+    size: Option<f64>,
+    filled_size: Option<f64>,
+    fill_cost: Option<f64>,
+    realized_pnl: Option<f64>,
 }
 
-// === Main ===
+impl TradeRecord {
+    /// Build a base record from a signal and the market's current book.
+    fn from_signal(market_id: &str, signal: &MomentumSignal, market: &Market) -> Self {
+        Self {
+            market_id: market_id.to_string(),
+            asset: signal.asset.clone(),
+            direction: signal.direction,
+            move_bps: signal.move_bps,
+            yes_ask: market.yes_ask,
+            yes_bid: market.yes_bid,
+            no_ask: market.no_ask,
+            no_bid: market.no_bid,
+            outcome: TradeOutcome::Skipped,
+            reason: None,
+            side: None,
+            price_cents: None,
+            size: None,
+            filled_size: None,
+            fill_cost: None,
+            realized_pnl: None,
+        }
+    }
 
-#[tokio::main]
+    fn skipped(mut self, reason: impl Into<String>) -> Self {
+        self.outcome = TradeOutcome::Skipped;
+        self.reason = Some(reason.into());
+        self
+    }
+
+    /// Build a record for a closed position (take-profit/stop-loss/max-hold),
+    /// with realized PnL computed from the entry/exit cents spread.
+    fn from_exit(
+        market_id: &str,
+        asset: &str,
+        side: &'static str,
+        entry_cents: i64,
+        exit_cents: i64,
+        contracts: f64,
+        reason: impl Into<String>,
+    ) -> Self {
+        let direction = if side == "YES" { Direction::Up } else { Direction::Down };
+        let realized_pnl = (exit_cents - entry_cents) as f64 / 100.0 * contracts;
+        Self {
+            market_id: market_id.to_string(),
+            asset: asset.to_string(),
+            direction,
+            move_bps: 0,
+            yes_ask: None,
+            yes_bid: None,
+            no_ask: None,
+            no_bid: None,
+            outcome: TradeOutcome::Closed,
+            reason: Some(reason.into()),
+            side: Some(side.to_string()),
+            price_cents: Some(exit_cents),
+            size: None,
+            filled_size: Some(contracts),
+            fill_cost: None,
+            realized_pnl: Some(realized_pnl),
+        }
+    }
+}
+
+/// Durable store for signals, decisions, and fills. Dry-run and live runs write
+/// into the same schema so recorded history can be replayed for tuning.
+#[async_trait]
+trait TradeStore: Send + Sync {
+    async fn record(&self, rec: TradeRecord) -> Result<()>;
+}
+
+/// Discards records; used when no `--db` path is configured.
+struct NullTradeStore;
+
+#[async_trait]
+impl TradeStore for NullTradeStore {
+    async fn record(&self, _rec: TradeRecord) -> Result<()> {
+        Ok(())
+    }
+}
+
+/// SQLite-backed [`TradeStore`].
+struct SqliteTradeStore {
+    conn: std::sync::Mutex<rusqlite::Connection>,
+}
+
+impl SqliteTradeStore {
+    fn open(path: &str) -> Result<Self> {
+        let conn = rusqlite::Connection::open(path)
+            .with_context(|| format!("failed to open trade db '{}'", path))?;
+        conn.execute_batch(
+            "CREATE TABLE IF NOT EXISTS trades (
+                id            INTEGER PRIMARY KEY AUTOINCREMENT,
+                ts            TEXT NOT NULL,
+                market_id     TEXT NOT NULL,
+                asset         TEXT NOT NULL,
+                direction     TEXT NOT NULL,
+                move_bps      INTEGER NOT NULL,
+                yes_ask       INTEGER,
+                yes_bid       INTEGER,
+                no_ask        INTEGER,
+                no_bid        INTEGER,
+                outcome       TEXT NOT NULL,
+                reason        TEXT,
+                side          TEXT,
+                price_cents   INTEGER,
+                size          REAL,
+                filled_size   REAL,
+                fill_cost     REAL,
+                realized_pnl  REAL
+            );",
+        )?;
+        Ok(Self {
+            conn: std::sync::Mutex::new(conn),
+        })
+    }
+}
+
+#[async_trait]
+impl TradeStore for SqliteTradeStore {
+    async fn record(&self, rec: TradeRecord) -> Result<()> {
+        let conn = self.conn.lock().expect("trade db mutex poisoned");
+        conn.execute(
+            "INSERT INTO trades
+                (ts, market_id, asset, direction, move_bps, yes_ask, yes_bid, no_ask, no_bid,
+                 outcome, reason, side, price_cents, size, filled_size, fill_cost, realized_pnl)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17)",
+            rusqlite::params![
+                Utc::now().to_rfc3339(),
+                rec.market_id,
+                rec.asset,
+                format!("{:?}", rec.direction),
+                rec.move_bps,
+                rec.yes_ask,
+                rec.yes_bid,
+                rec.no_ask,
+                rec.no_bid,
+                rec.outcome.as_str(),
+                rec.reason,
+                rec.side,
+                rec.price_cents,
+                rec.size,
+                rec.filled_size,
+                rec.fill_cost,
+                rec.realized_pnl,
+            ],
+        )?;
+        Ok(())
+    }
+}
+
+/// Build the configured trade store (SQLite when a path is given, else a no-op).
+fn make_trade_store(db_path: Option<&str>) -> Result<Arc<dyn TradeStore>> {
+    match db_path {
+        Some(path) => Ok(Arc::new(SqliteTradeStore::open(path)?)),
+        None => Ok(Arc::new(NullTradeStore)),
+    }
+}
+
+// === Book-mid candle persistence ===
+
+/// Resolutions we aggregate book mid-price candles at.
+const BOOK_CANDLE_RESOLUTIONS: &[(&str, i64)] = &[("1s", 1), ("1m", 60), ("1h", 3600)];
+
+/// A completed OHLC candle of a market's book mid-price.
+#[derive(Debug, Clone)]
+struct BookCandle {
+    market_id: String,
+    resolution: String,
+    start_time: chrono::DateTime<Utc>,
+    open: f64,
+    high: f64,
+    low: f64,
+    close: f64,
+    volume: i64,
+}
+
+/// In-progress candle bucket.
+#[derive(Debug, Clone)]
+struct OpenCandle {
+    bucket: i64,
+    open: f64,
+    high: f64,
+    low: f64,
+    close: f64,
+    volume: i64,
+}
+
+/// Rolls book mid-price observations into fixed time buckets per
+/// `(market, resolution)`, emitting a [`BookCandle`] each time a bucket closes.
+#[derive(Default)]
+struct CandleAggregator {
+    open: HashMap<(String, &'static str), OpenCandle>,
+}
+
+impl CandleAggregator {
+    /// Fold a mid-price observation into every resolution, returning any
+    /// candles completed by crossing a bucket boundary.
+    fn observe(&mut self, market_id: &str, mid: f64, now_secs: i64) -> Vec<BookCandle> {
+        let mut completed = Vec::new();
+
+        for (label, secs) in BOOK_CANDLE_RESOLUTIONS {
+            let bucket = now_secs - now_secs.rem_euclid(*secs);
+            let key = (market_id.to_string(), *label);
+
+            match self.open.get_mut(&key) {
+                Some(c) if c.bucket == bucket => {
+                    c.high = c.high.max(mid);
+                    c.low = c.low.min(mid);
+                    c.close = mid;
+                    c.volume += 1;
+                }
+                _ => {
+                    let fresh = OpenCandle {
+                        bucket,
+                        open: mid,
+                        high: mid,
+                        low: mid,
+                        close: mid,
+                        volume: 1,
+                    };
+                    if let Some(prev) = self.open.insert(key, fresh) {
+                        completed.push(BookCandle {
+                            market_id: market_id.to_string(),
+                            resolution: label.to_string(),
+                            start_time: chrono::DateTime::from_timestamp(prev.bucket, 0)
+                                .unwrap_or_else(Utc::now),
+                            open: prev.open,
+                            high: prev.high,
+                            low: prev.low,
+                            close: prev.close,
+                            volume: prev.volume,
+                        });
+                    }
+                }
+            }
+        }
+
+        completed
+    }
+}
+
+/// Postgres-backed candle sink using a `deadpool-postgres` pool.
+struct PgCandleStore {
+    pool: deadpool_postgres::Pool,
+}
+
+impl PgCandleStore {
+    async fn connect(url: &str) -> Result<Self> {
+        let pg_config: tokio_postgres::Config = url.parse().context("invalid --pg-url")?;
+        let mgr = deadpool_postgres::Manager::new(pg_config, tokio_postgres::NoTls);
+        let pool = deadpool_postgres::Pool::builder(mgr)
+            .max_size(8)
+            .build()
+            .context("failed to build pg pool")?;
+
+        let client = pool.get().await?;
+        client
+            .batch_execute(
+                "CREATE TABLE IF NOT EXISTS candles (
+                    market_id   TEXT NOT NULL,
+                    resolution  TEXT NOT NULL,
+                    start_time  TIMESTAMPTZ NOT NULL,
+                    open        DOUBLE PRECISION NOT NULL,
+                    high        DOUBLE PRECISION NOT NULL,
+                    low         DOUBLE PRECISION NOT NULL,
+                    close       DOUBLE PRECISION NOT NULL,
+                    volume      BIGINT NOT NULL,
+                    PRIMARY KEY (market_id, resolution, start_time)
+                );",
+            )
+            .await?;
+        Ok(Self { pool })
+    }
+
+    /// Upsert a batch of candles in a single statement so a late update into an
+    /// existing bucket merges (GREATEST/LEAST) rather than duplicating a row.
+    async fn upsert_batch(&self, candles: &[BookCandle]) -> Result<()> {
+        if candles.is_empty() {
+            return Ok(());
+        }
+
+        let mut sql = String::from(
+            "INSERT INTO candles \
+             (market_id, resolution, start_time, open, high, low, close, volume) VALUES ",
+        );
+        let mut params: Vec<&(dyn tokio_postgres::types::ToSql + Sync)> = Vec::new();
+        for (i, c) in candles.iter().enumerate() {
+            let base = i * 8;
+            if i > 0 {
+                sql.push_str(", ");
+            }
+            sql.push_str(&format!(
+                "(${}, ${}, ${}, ${}, ${}, ${}, ${}, ${})",
+                base + 1, base + 2, base + 3, base + 4, base + 5, base + 6, base + 7, base + 8
+            ));
+            params.push(&c.market_id);
+            params.push(&c.resolution);
+            params.push(&c.start_time);
+            params.push(&c.open);
+            params.push(&c.high);
+            params.push(&c.low);
+            params.push(&c.close);
+            params.push(&c.volume);
+        }
+        sql.push_str(
+            " ON CONFLICT (market_id, resolution, start_time) DO UPDATE SET \
+               high = GREATEST(candles.high, excluded.high), \
+               low = LEAST(candles.low, excluded.low), \
+               close = excluded.close, \
+               volume = candles.volume + excluded.volume",
+        );
+
+        let client = self.pool.get().await?;
+        client.execute(sql.as_str(), &params).await?;
+        Ok(())
+    }
+}
+
+// === Notifications ===
+
+/// Delivers noteworthy [`BotEvent`]s to an operator out-of-band.
+#[async_trait]
+trait Notifier: Send + Sync {
+    async fn notify(&self, event: &BotEvent) -> Result<()>;
+}
+
+/// Posts each event as JSON to a webhook URL. A Telegram bot `sendMessage`
+/// endpoint or a generic webhook both fit this shape.
+struct WebhookNotifier {
+    client: reqwest::Client,
+    url: String,
+}
+
+impl WebhookNotifier {
+    fn new(url: String) -> Result<Self> {
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(10))
+            .build()?;
+        Ok(Self { client, url })
+    }
+}
+
+#[async_trait]
+impl Notifier for WebhookNotifier {
+    async fn notify(&self, event: &BotEvent) -> Result<()> {
+        self.client.post(&self.url).json(event).send().await?;
+        Ok(())
+    }
+}
+
+/// Events an operator is paged about: fills/submissions, position closes,
+/// and feed drops.
+fn is_notable(event: &BotEvent) -> bool {
+    matches!(
+        event,
+        BotEvent::TradeSubmitted { .. }
+            | BotEvent::TradeFilled { .. }
+            | BotEvent::PositionClosed { .. }
+            | BotEvent::FeedDisconnected { .. }
+    )
+}
+
+/// A reconnect that stays up at least this long counts as a fresh
+/// connection — a later drop starts a new outage rather than extending the
+/// current one.
+const RECONNECT_ALIVE_RESET: Duration = Duration::from_secs(10);
+
+/// Debounces `FeedDisconnected` so a socket flapping through brief reconnect
+/// attempts doesn't page on every blip — only once an outage has stayed down
+/// for at least `alert_after`.
+struct DisconnectDebouncer {
+    alert_after: Duration,
+    down_since: Option<Instant>,
+    alerted: bool,
+}
+
+impl DisconnectDebouncer {
+    fn new(alert_after_secs: u64) -> Self {
+        Self {
+            alert_after: Duration::from_secs(alert_after_secs),
+            down_since: None,
+            alerted: false,
+        }
+    }
+
+    /// Record one disconnect, given how long the connection had stayed up
+    /// before it dropped. Returns `true` the first time this outage has
+    /// stayed down for at least `alert_after`.
+    fn on_disconnect(&mut self, was_up_for: Duration) -> bool {
+        if was_up_for >= RECONNECT_ALIVE_RESET || self.down_since.is_none() {
+            self.down_since = Some(Instant::now());
+            self.alerted = false;
+        }
+        if !self.alerted && self.down_since.expect("set above").elapsed() >= self.alert_after {
+            self.alerted = true;
+            return true;
+        }
+        false
+    }
+}
+
+/// Forward notable events from the bus to the notifier.
+fn spawn_notifier(mut rx: broadcast::Receiver<BotEvent>, notifier: Arc<dyn Notifier>) {
+    tokio::spawn(async move {
+        loop {
+            match rx.recv().await {
+                Ok(event) => {
+                    if is_notable(&event) {
+                        if let Err(e) = notifier.notify(&event).await {
+                            warn!("[NOTIFY] delivery failed: {}", e);
+                        }
+                    }
+                }
+                Err(broadcast::error::RecvError::Lagged(n)) => {
+                    warn!("[NOTIFY] lagged, dropped {} events", n);
+                }
+                Err(broadcast::error::RecvError::Closed) => break,
+            }
+        }
+    });
+}
+
+// === Fills broadcast server ===
+
+/// A connected fills-stream subscriber. Live fills are forwarded through this
+/// channel rather than written to the socket directly, so one slow reader
+/// can't block the broadcaster or other peers.
+struct FillsPeer {
+    tx: mpsc::UnboundedSender<Message>,
+}
+
+/// Subscribers of the fills WebSocket server, keyed by peer address.
+type PeerMap = Arc<std::sync::Mutex<HashMap<std::net::SocketAddr, FillsPeer>>>;
+
+/// Per-market snapshot sent to a fills subscriber on connect, before it
+/// starts receiving live [`FillMessage`]s.
+#[derive(Serialize)]
+struct MarketCheckpoint {
+    market_id: String,
+    asset: String,
+    yes_bid: Option<i64>,
+    yes_ask: Option<i64>,
+    no_bid: Option<i64>,
+    no_ask: Option<i64>,
+    last_trade_ms_ago: Option<u64>,
+}
+
+impl MarketCheckpoint {
+    fn from_market(market_id: &str, m: &Market) -> Self {
+        Self {
+            market_id: market_id.to_string(),
+            asset: m.asset.clone(),
+            yes_bid: m.yes_bid,
+            yes_ask: m.yes_ask,
+            no_bid: m.no_bid,
+            no_ask: m.no_ask,
+            last_trade_ms_ago: m.last_trade_time.map(|t| t.elapsed().as_millis() as u64),
+        }
+    }
+}
+
+/// Wire format for a fill, broadcast verbatim to every connected subscriber.
+#[derive(Serialize)]
+struct FillMessage {
+    market_id: String,
+    asset: String,
+    side: String,
+    filled_size: f64,
+    fill_cost: f64,
+    order_id: String,
+    timestamp: chrono::DateTime<Utc>,
+}
+
+/// Accept loop for the fills WebSocket server. Each connection first gets a
+/// checkpoint of current per-market state, then live fills as they happen —
+/// mirroring the checkpoint-then-stream pattern downstream consumers expect.
+async fn run_fills_server(addr: String, state: Arc<RwLock<State>>, peers: PeerMap) -> Result<()> {
+    let listener = tokio::net::TcpListener::bind(&addr)
+        .await
+        .with_context(|| format!("failed to bind --fills-addr '{}'", addr))?;
+    info!("[FILLS] Serving fills stream on ws://{}", addr);
+
+    loop {
+        let (stream, peer_addr) = match listener.accept().await {
+            Ok(conn) => conn,
+            Err(e) => {
+                warn!("[FILLS] Accept failed: {}", e);
+                continue;
+            }
+        };
+        let state = state.clone();
+        let peers = peers.clone();
+        tokio::spawn(async move {
+            if let Err(e) = handle_fills_peer(stream, peer_addr, state, peers.clone()).await {
+                warn!("[FILLS] {} error: {}", peer_addr, e);
+            }
+            peers.lock().expect("peer map mutex poisoned").remove(&peer_addr);
+            info!("[FILLS] {} unsubscribed", peer_addr);
+        });
+    }
+}
+
+/// Handshake, send the checkpoint, then pump queued fills to one peer until
+/// it disconnects. Inbound frames are read and discarded — this is a
+/// publish-only stream.
+async fn handle_fills_peer(
+    stream: tokio::net::TcpStream,
+    peer_addr: std::net::SocketAddr,
+    state: Arc<RwLock<State>>,
+    peers: PeerMap,
+) -> Result<()> {
+    let ws = tokio_tungstenite::accept_async(stream)
+        .await
+        .context("fills ws handshake failed")?;
+    let (mut write, mut read) = ws.split();
+
+    let checkpoint = {
+        let s = state.read().await;
+        s.markets
+            .iter()
+            .map(|(id, m)| MarketCheckpoint::from_market(id, m))
+            .collect::<Vec<_>>()
+    };
+    write.send(Message::Text(serde_json::to_string(&checkpoint)?)).await?;
+
+    let (tx, mut rx) = mpsc::unbounded_channel::<Message>();
+    peers.lock().expect("peer map mutex poisoned").insert(peer_addr, FillsPeer { tx });
+    info!("[FILLS] {} subscribed", peer_addr);
+
+    loop {
+        tokio::select! {
+            msg = rx.recv() => match msg {
+                Some(msg) => {
+                    if write.send(msg).await.is_err() {
+                        break;
+                    }
+                }
+                None => break,
+            },
+            frame = read.next() => match frame {
+                Some(Ok(Message::Close(_))) | None => break,
+                Some(Err(_)) => break,
+                _ => {}
+            },
+        }
+    }
+
+    Ok(())
+}
+
+/// Forward `TradeFilled` events from the internal bus to every connected
+/// fills subscriber.
+fn spawn_fills_broadcaster(mut rx: broadcast::Receiver<BotEvent>, peers: PeerMap) {
+    tokio::spawn(async move {
+        loop {
+            match rx.recv().await {
+                Ok(BotEvent::TradeFilled { market_id, asset, side, filled_size, fill_cost, order_id }) => {
+                    let msg = FillMessage {
+                        market_id,
+                        asset,
+                        side,
+                        filled_size,
+                        fill_cost,
+                        order_id,
+                        timestamp: Utc::now(),
+                    };
+                    let Ok(text) = serde_json::to_string(&msg) else { continue };
+                    let peers = peers.lock().expect("peer map mutex poisoned");
+                    for peer in peers.values() {
+                        let _ = peer.tx.send(Message::Text(text.clone()));
+                    }
+                }
+                Ok(_) => {}
+                Err(broadcast::error::RecvError::Lagged(n)) => {
+                    warn!("[FILLS] lagged, dropped {} events", n);
+                }
+                Err(broadcast::error::RecvError::Closed) => break,
+            }
+        }
+    });
+}
+
+// === Metrics ===
+
+/// A monotonically increasing counter backed by an [`AtomicU64`].
+#[derive(Clone)]
+struct Counter(Arc<std::sync::atomic::AtomicU64>);
+
+impl Counter {
+    fn inc(&self) {
+        self.0.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+    }
+
+    fn get(&self) -> u64 {
+        self.0.load(std::sync::atomic::Ordering::Relaxed)
+    }
+}
+
+/// A point-in-time value backed by an [`AtomicU64`] (e.g. a millisecond
+/// staleness reading); unlike [`Counter`] it can move up or down.
+#[derive(Clone)]
+struct Gauge(Arc<std::sync::atomic::AtomicU64>);
+
+impl Gauge {
+    fn set(&self, value: u64) {
+        self.0.store(value, std::sync::atomic::Ordering::Relaxed);
+    }
+
+    fn get(&self) -> u64 {
+        self.0.load(std::sync::atomic::Ordering::Relaxed)
+    }
+}
+
+/// Named counters and gauges, rendered as Prometheus text exposition format.
+///
+/// Handles are registered (and deduplicated) by name, so any hot path can call
+/// `registry.counter("x")` without threading a handle through every caller.
+#[derive(Default)]
+struct MetricsRegistry {
+    counters: std::sync::Mutex<HashMap<&'static str, Counter>>,
+    gauges: std::sync::Mutex<HashMap<&'static str, Gauge>>,
+}
+
+impl MetricsRegistry {
+    /// Look up a counter by name, registering it on first use.
+    fn counter(&self, name: &'static str) -> Counter {
+        self.counters
+            .lock()
+            .expect("counters mutex poisoned")
+            .entry(name)
+            .or_insert_with(|| Counter(Arc::new(std::sync::atomic::AtomicU64::new(0))))
+            .clone()
+    }
+
+    /// Look up a gauge by name, registering it on first use.
+    fn gauge(&self, name: &'static str) -> Gauge {
+        self.gauges
+            .lock()
+            .expect("gauges mutex poisoned")
+            .entry(name)
+            .or_insert_with(|| Gauge(Arc::new(std::sync::atomic::AtomicU64::new(0))))
+            .clone()
+    }
+
+    /// Render all registered metrics in Prometheus text exposition format.
+    fn render(&self) -> String {
+        let mut out = String::new();
+        let counters = self.counters.lock().expect("counters mutex poisoned");
+        for (name, c) in counters.iter() {
+            out.push_str(&format!("# TYPE {} counter\n{} {}\n", name, name, c.get()));
+        }
+        let gauges = self.gauges.lock().expect("gauges mutex poisoned");
+        for (name, g) in gauges.iter() {
+            out.push_str(&format!("# TYPE {} gauge\n{} {}\n", name, name, g.get()));
+        }
+        out
+    }
+}
+
+/// Serve `registry` as a tiny Prometheus-scrapable HTTP endpoint on `addr`.
+/// Any request gets the same plaintext `/metrics` body back — this is a
+/// scrape target, not a general-purpose web server.
+async fn serve_metrics(registry: Arc<MetricsRegistry>, addr: String) -> Result<()> {
+    let listener = tokio::net::TcpListener::bind(&addr)
+        .await
+        .with_context(|| format!("failed to bind --metrics-addr '{}'", addr))?;
+    info!("[METRICS] Serving on http://{}/metrics", addr);
+
+    loop {
+        let (mut socket, _) = match listener.accept().await {
+            Ok(conn) => conn,
+            Err(e) => {
+                warn!("[METRICS] Accept failed: {}", e);
+                continue;
+            }
+        };
+        let registry = registry.clone();
+        tokio::spawn(async move {
+            use tokio::io::{AsyncReadExt, AsyncWriteExt};
+            // We only ever serve one fixed body, so the request itself is
+            // read and discarded without parsing the method or path.
+            let mut buf = [0u8; 1024];
+            let _ = socket.read(&mut buf).await;
+
+            let body = registry.render();
+            let response = format!(
+                "HTTP/1.1 200 OK\r\nContent-Type: text/plain; version=0.0.4\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                body.len(),
+                body
+            );
+            let _ = socket.write_all(response.as_bytes()).await;
+        });
+    }
+}
+
+/// Periodically set `orderbook_staleness_ms` to the time since the most
+/// recently traded market's `last_trade_time`, so a scraper can alert when
+/// the bot has gone quiet. `0` until the first trade of the run.
+async fn run_staleness_gauge(state: Arc<RwLock<State>>, gauge: Gauge) {
+    let mut ticker = tokio::time::interval(Duration::from_secs(1));
+    loop {
+        ticker.tick().await;
+        let staleness_ms = {
+            let s = state.read().await;
+            s.markets
+                .values()
+                .filter_map(|m| m.last_trade_time)
+                .map(|t| t.elapsed().as_millis() as u64)
+                .min()
+        };
+        gauge.set(staleness_ms.unwrap_or(0));
+    }
+}
+
+// === Market rollover ===
+
+/// Watch each market's time to expiry and roll to the next contract in the
+/// series before the current one dies.
+///
+/// The 15-minute up/down series roll every 15 minutes; once a market expires
+/// the bot is subscribed to a dead token and stops trading. When a market is
+/// within `within_secs` of expiry (or already gone), this re-runs
+/// `discover_markets` for that asset, swaps the fresh `Market` into state, and
+/// flags the WS loop to resubscribe. Per-asset `last_trade_time` is carried
+/// forward so the cooldown survives the rollover and we don't double-trade the
+/// fresh market.
+async fn run_rollover(state: Arc<RwLock<State>>, asset_filter: Option<String>, within_secs: u64) {
+    let mut ticker = tokio::time::interval(Duration::from_secs(5));
+    loop {
+        ticker.tick().await;
+
+        // Snapshot markets that are at or past the rollover threshold.
+        let stale: Vec<(String, String, Option<Instant>)> = {
+            let s = state.read().await;
+            s.markets
+                .iter()
+                .filter(|(_, m)| {
+                    m.seconds_to_expiry()
+                        .map(|secs| secs <= within_secs as f64)
+                        .unwrap_or(true)
+                })
+                .map(|(id, m)| (id.clone(), m.asset.clone(), m.last_trade_time))
+                .collect()
+        };
+
+        for (old_id, asset, last_trade) in stale {
+            // Respect an explicit asset filter so we don't discover others.
+            if let Some(filter) = &asset_filter {
+                if !filter.eq_ignore_ascii_case(&asset) {
+                    continue;
+                }
+            }
+
+            // Hold off swapping in a new contract while a position from the
+            // old one is still open: `Position` is keyed by the old token,
+            // and the exit evaluator only matches positions against
+            // `State::markets`, so dropping the old market here would orphan
+            // it with no way to ever close. Retry next tick once it exits.
+            let has_open_position = {
+                let s = state.read().await;
+                s.markets.get(&old_id).is_some_and(|m| {
+                    s.positions.contains_key(&m.yes_token) || s.positions.contains_key(&m.no_token)
+                })
+            };
+            if has_open_position {
+                info!("[ROLLOVER] {} has an open position, deferring roll", asset);
+                continue;
+            }
+
+            match discover_markets(Some(&asset)).await {
+                Ok(fresh) => {
+                    let Some(mut market) = fresh.into_iter().find(|m| m.asset == asset) else {
+                        continue;
+                    };
+                    // Same contract still soonest — nothing has rolled yet.
+                    if market.condition_id == old_id {
+                        continue;
+                    }
+
+                    // Carry the cooldown forward into the new contract.
+                    market.last_trade_time = last_trade;
+                    let new_id = market.condition_id.clone();
+
+                    let mut s = state.write().await;
+                    s.markets.remove(&old_id);
+                    s.markets.insert(new_id.clone(), market);
+                    s.subscription_dirty = true;
+                    s.publish(BotEvent::MarketRolledOver {
+                        asset: asset.clone(),
+                        new_market_id: new_id.clone(),
+                    });
+                    info!("[ROLLOVER] {} -> new market {}", asset, new_id);
+                }
+                Err(e) => warn!("[ROLLOVER] {} rediscovery failed: {}", asset, e),
+            }
+        }
+    }
+}
+
+// === Polymarket WebSocket ===
+
+/// A full top-of-book-and-beyond snapshot for one token, tagged with the
+/// sequence number subsequent `price_change` deltas continue from.
+#[derive(Deserialize, Debug)]
+struct BookSnapshot {
+    asset_id: String,
+    #[serde(default)]
+    seq: u64,
+    bids: Vec<PriceLevel>,
+    asks: Vec<PriceLevel>,
+}
+
+/// An incremental change to one token's book: zero or more bid/ask levels
+/// that were added, updated, or removed (`size == 0`) since `seq - 1`.
+#[derive(Deserialize, Debug, Clone)]
+struct BookDelta {
+    asset_id: String,
+    seq: u64,
+    #[serde(default)]
+    bid_changes: Vec<PriceLevel>,
+    #[serde(default)]
+    ask_changes: Vec<PriceLevel>,
+}
+
+/// Which side of the book a print took liquidity from.
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "UPPERCASE")]
+enum DealSide {
+    Buy,
+    Sell,
+}
+
+/// An executed print: price/size that actually crossed, plus the resting
+/// size left at that price level afterward. Carrying `remaining_size` lets
+/// us reconcile the ladder in the same pass as recording the deal, so a
+/// print that empties a level both records the trade and removes the level.
+#[derive(Deserialize, Debug, Clone)]
+struct TradePrint {
+    asset_id: String,
+    side: DealSide,
+    price: String,
+    size: String,
+    #[serde(default)]
+    remaining_size: String,
+}
+
+/// Tagged union of the messages the market-data channel sends; `event_type`
+/// picks the variant the way Polymarket's feed discriminates `book` and
+/// `price_change` (resting-order revisions, no trade) from
+/// `last_trade_price` (an executed deal).
+#[derive(Deserialize, Debug)]
+#[serde(tag = "event_type", rename_all = "snake_case")]
+enum BookEvent {
+    Book(BookSnapshot),
+    PriceChange(BookDelta),
+    LastTradePrice(TradePrint),
+}
+
+#[derive(Deserialize, Debug, Clone)]
+struct PriceLevel {
+    price: String,
+    size: String,
+}
+
+/// How many out-of-order deltas a side will buffer while waiting for the
+/// gap to fill before giving up and waiting for a fresh snapshot instead.
+const MAX_PENDING_DELTAS: usize = 32;
+
+/// One token's live order book: a full `price_cents -> size` ladder per
+/// side, plus the sequencing state needed to apply deltas correctly across
+/// the reconnect window (where a stale snapshot and fresh deltas, or deltas
+/// out of order, can interleave).
+#[derive(Debug, Default, Clone)]
+struct OrderBookSide {
+    bids: std::collections::BTreeMap<i64, f64>,
+    asks: std::collections::BTreeMap<i64, f64>,
+    last_seq: Option<u64>,
+    /// Deltas that arrived ahead of `last_seq + 1`, held until the gap fills.
+    pending: std::collections::BTreeMap<u64, BookDelta>,
+}
+
+impl OrderBookSide {
+    fn best_bid_cents(&self) -> Option<i64> {
+        self.bids.keys().next_back().copied()
+    }
+
+    fn best_ask_cents(&self) -> Option<i64> {
+        self.asks.keys().next().copied()
+    }
+
+    /// A full snapshot always wins: replace the ladder outright and anchor
+    /// future deltas to `seq`.
+    fn apply_snapshot(&mut self, seq: u64, bids: &[PriceLevel], asks: &[PriceLevel]) {
+        self.bids.clear();
+        self.asks.clear();
+        for level in bids {
+            upsert_level(&mut self.bids, level);
+        }
+        for level in asks {
+            upsert_level(&mut self.asks, level);
+        }
+        self.last_seq = Some(seq);
+        self.pending.clear();
+    }
+
+    /// Apply a delta if it's next in sequence, buffer it if it's ahead (a
+    /// gap we might still fill), or drop it if it's stale. Exceeding
+    /// `MAX_PENDING_DELTAS` means the gap is unbridgeable from here, so the
+    /// ladder is cleared and we wait for the next full snapshot to resync.
+    fn apply_delta(&mut self, delta: BookDelta) {
+        let Some(last) = self.last_seq else {
+            // No snapshot yet to anchor this delta to.
+            return;
+        };
+        if delta.seq <= last {
+            return; // stale/duplicate
+        }
+        if delta.seq == last + 1 {
+            self.apply_one(&delta);
+            self.last_seq = Some(delta.seq);
+            self.drain_pending();
+        } else if self.pending.len() >= MAX_PENDING_DELTAS {
+            self.bids.clear();
+            self.asks.clear();
+            self.last_seq = None;
+            self.pending.clear();
+        } else {
+            self.pending.insert(delta.seq, delta);
+        }
+    }
+
+    fn apply_one(&mut self, delta: &BookDelta) {
+        for level in &delta.bid_changes {
+            upsert_level(&mut self.bids, level);
+        }
+        for level in &delta.ask_changes {
+            upsert_level(&mut self.asks, level);
+        }
+    }
+
+    /// Replay any buffered deltas that are now contiguous with `last_seq`.
+    fn drain_pending(&mut self) {
+        while let Some(next) = self.last_seq.and_then(|last| self.pending.remove(&(last + 1))) {
+            let seq = next.seq;
+            self.apply_one(&next);
+            self.last_seq = Some(seq);
+        }
+    }
+
+    /// Reconcile the ladder after an executed print. The taker's side tells
+    /// us which resting side it ate from (a buy takes from the asks, a sell
+    /// from the bids); `remaining_size` is the level's new resting size.
+    fn apply_trade(&mut self, taker_side: DealSide, price_cents: i64, remaining_size: f64) {
+        let ladder = match taker_side {
+            DealSide::Buy => &mut self.asks,
+            DealSide::Sell => &mut self.bids,
+        };
+        set_level(ladder, price_cents, remaining_size);
+    }
+}
+
+/// Insert or remove one price level (`size == 0` means the level is gone).
+fn upsert_level(ladder: &mut std::collections::BTreeMap<i64, f64>, level: &PriceLevel) {
+    let price = parse_price_cents(&level.price);
+    let size = level.size.parse::<f64>().unwrap_or(0.0);
+    set_level(ladder, price, size);
+}
+
+/// Shared by `upsert_level` and `OrderBookSide::apply_trade`.
+fn set_level(ladder: &mut std::collections::BTreeMap<i64, f64>, price_cents: i64, size: f64) {
+    if size <= 0.0 {
+        ladder.remove(&price_cents);
+    } else {
+        ladder.insert(price_cents, size);
+    }
+}
+
+/// How long a print is kept in a token's rolling trade tape before aging out.
+const TRADE_TAPE_WINDOW: Duration = Duration::from_secs(60);
+/// Hard cap on retained prints, independent of age, so a burst of trades
+/// can't grow the tape unbounded between evictions.
+const TRADE_TAPE_MAX_PRINTS: usize = 200;
+
+/// One recorded print in a token's rolling trade tape.
+#[derive(Debug, Clone)]
+struct Deal {
+    price_cents: i64,
+    size: f64,
+    side: DealSide,
+    at: Instant,
+}
+
+/// Rolling window of recent executed prints for one token, used to confirm
+/// momentum signals against real flow rather than flickering quotes.
+#[derive(Debug, Clone, Default)]
+struct TradeTape {
+    deals: VecDeque<Deal>,
+}
+
+impl TradeTape {
+    fn record(&mut self, deal: Deal) {
+        self.deals.push_back(deal);
+        while self.deals.len() > TRADE_TAPE_MAX_PRINTS {
+            self.deals.pop_front();
+        }
+        self.evict_stale();
+    }
+
+    fn evict_stale(&mut self) {
+        while self.deals.front().is_some_and(|d| d.at.elapsed() > TRADE_TAPE_WINDOW) {
+            self.deals.pop_front();
+        }
+    }
+
+    /// Volume-weighted average price (cents) over the retained window, or
+    /// `None` if no prints have landed yet.
+    fn vwap_cents(&self) -> Option<f64> {
+        let (notional, size) = self.deals.iter()
+            .fold((0.0, 0.0), |(n, s), d| (n + d.price_cents as f64 * d.size, s + d.size));
+        (size > 0.0).then(|| notional / size)
+    }
+
+    /// Total traded size over the retained window.
+    fn traded_volume(&self) -> f64 {
+        self.deals.iter().map(|d| d.size).sum()
+    }
+
+    /// Most recent `n` prints, oldest first.
+    fn last_n(&self, n: usize) -> impl Iterator<Item = &Deal> {
+        let skip = self.deals.len().saturating_sub(n);
+        self.deals.iter().skip(skip)
+    }
+}
+
+#[derive(Serialize)]
+struct SubscribeCmd {
+    assets_ids: Vec<String>,
+    #[serde(rename = "type")]
+    sub_type: &'static str,
+}
+
+fn parse_price_cents(s: &str) -> i64 {
+    s.parse::<f64>()
+        .map(|p| (p * 100.0).round() as i64)
+        .unwrap_or(0)
+}
+
+// === Backtesting: record & replay ===
+
+/// One inbound item worth replaying: either a raw Polymarket market-data
+/// frame (fed back through the same `BookEvent` parsing the live feed uses)
+/// or a price-feed tick (fed back through the same signal-detection code).
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+enum CapturedEvent {
+    Book { raw: String },
+    Price { source: String, asset: String, price: f64 },
+}
+
+/// One line of a capture file: a `CapturedEvent` plus how long after the
+/// previous record it arrived, so replay can reproduce inter-arrival timing
+/// at any speed multiplier.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+struct CapturedRecord {
+    delta_ms: u64,
+    event: CapturedEvent,
+}
+
+/// Appends every inbound market-data/price-feed event to an append-only
+/// file, so `run_backtest` can replay the exact sequence and timing later.
+struct Recorder {
+    file: std::sync::Mutex<std::fs::File>,
+    last: std::sync::Mutex<Option<Instant>>,
+}
+
+impl Recorder {
+    fn open(path: &str) -> Result<Self> {
+        let file = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .with_context(|| format!("failed to open record file '{}'", path))?;
+        Ok(Self {
+            file: std::sync::Mutex::new(file),
+            last: std::sync::Mutex::new(None),
+        })
+    }
+
+    fn record(&self, event: CapturedEvent) {
+        let now = Instant::now();
+        let delta_ms = {
+            let mut last = self.last.lock().unwrap();
+            let delta_ms = last.map(|t| now.duration_since(t).as_millis() as u64).unwrap_or(0);
+            *last = Some(now);
+            delta_ms
+        };
+
+        let Ok(line) = serde_json::to_string(&CapturedRecord { delta_ms, event }) else { return };
+        use std::io::Write;
+        let mut file = self.file.lock().unwrap();
+        let _ = writeln!(file, "{}", line);
+    }
+}
+
+/// Aggregate stats from a backtest replay. "Hit rate" is the fraction of
+/// signals that cleared every buy check (cooldown, ask availability, flow,
+/// model edge, inventory cap) and would have been bought; `paper_pnl_cents`
+/// sums `(exit bid - entry) * contracts` over paper positions closed by the
+/// take-profit/stop-loss/max-hold evaluator, plus positions still open at
+/// EOF marked to their last known bid.
+#[derive(Debug, Default)]
+struct BacktestReport {
+    signals: u64,
+    trades: u64,
+    paper_pnl_cents: f64,
+}
+
+impl BacktestReport {
+    fn hit_rate(&self) -> f64 {
+        if self.signals == 0 { 0.0 } else { self.trades as f64 / self.signals as f64 }
+    }
+}
+
+/// Replay a file captured via `--record-to` through the exact same
+/// book/tape and signal-detection code the live bot uses (`apply_book_event`,
+/// `ingest_price_update`), exercising the buy decision in dry-run only and
+/// reporting aggregate fills/P&L/hit-rate at the end. This is the file
+/// source behind the same update/signal code the live WS source drives, so
+/// a strategy change can be validated offline before risking capital.
+async fn run_backtest(args: &Args, path: &str) -> Result<()> {
+    info!("═══════════════════════════════════════════════════════════════════════");
+    info!("📼 BACKTEST REPLAY: {}", path);
+    info!("   Speed: {}", if args.replay_speed <= 0.0 {
+        "as-fast-as-possible".to_string()
+    } else {
+        format!("{}x", args.replay_speed)
+    });
+    info!("═══════════════════════════════════════════════════════════════════════");
+
+    let resolution = Resolution::parse(&args.candle_res)
+        .with_context(|| format!("invalid --candle-res '{}' (expected 1s, 5s, 1m)", args.candle_res))?;
+
+    info!("[DISCOVER] Searching for markets...");
+    let discovered = discover_markets(args.asset.as_deref()).await?;
+    info!("[DISCOVER] Found {} markets", discovered.len());
+    if discovered.is_empty() {
+        warn!("No markets found!");
+        return Ok(());
+    }
+
+    let mut state = State::new();
+    state.resolution = resolution;
+    for m in discovered {
+        let id = m.condition_id.clone();
+        state.markets.insert(id, m);
+    }
+
+    let window_secs = args.window_secs;
+    let z_min = args.z_min;
+    let threshold_bps = args.threshold_bps;
+    let min_confirmations = args.min_confirmations.max(1);
+    let cooldown = Duration::from_secs(args.cooldown);
+    let edge_threshold = args.edge;
+    let spread_bps = args.spread_bps;
+    let size = args.size;
+    let max_inventory = args.max_inventory;
+    let min_flow_volume = args.min_flow_volume;
+    let take_profit_cents = args.take_profit;
+    let stop_loss_cents = args.stop_loss;
+    let max_hold = Duration::from_secs(args.max_hold_secs);
+    let fair_value_model = make_fair_value_model(&args.model, args.k)?;
+    let trade_store = make_trade_store(args.db.as_deref())?;
+
+    let file = std::fs::File::open(path)
+        .with_context(|| format!("failed to open replay file '{}'", path))?;
+    let reader = std::io::BufReader::new(file);
+
+    let mut report = BacktestReport::default();
+
+    use std::io::BufRead;
+    for line in reader.lines() {
+        let line = line.context("failed to read replay record")?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let record: CapturedRecord = serde_json::from_str(&line)
+            .context("failed to parse replay record")?;
+
+        if args.replay_speed > 0.0 && record.delta_ms > 0 {
+            let scaled_ms = (record.delta_ms as f64 / args.replay_speed).round() as u64;
+            tokio::time::sleep(Duration::from_millis(scaled_ms)).await;
+        }
+
+        match record.event {
+            CapturedEvent::Book { raw } => {
+                if let Ok(events) = serde_json::from_str::<Vec<BookEvent>>(&raw) {
+                    for event in events {
+                        state.apply_book_event(event);
+                    }
+                }
+            }
+            CapturedEvent::Price { source, asset, price } => {
+                let update = PriceUpdate { source, asset, price, at: Instant::now() };
+                state.ingest_price_update(&update, window_secs, z_min, threshold_bps, min_confirmations);
+            }
+        }
+
+        // Evaluate pending signals through the same cooldown/flow/edge
+        // checks the live loop's periodic check runs, always via the
+        // dry-run path (replay never submits a real order).
+        state.pending_signals.retain(|sig| sig.triggered_at.elapsed() < Duration::from_secs(5));
+        let signals: Vec<MomentumSignal> = state.pending_signals.drain(..).collect();
+
+        for signal in signals {
+            report.signals += 1;
+
+            let market_entry = state.markets.iter_mut().find(|(_, m)| m.asset == signal.asset);
+            let Some((market_id, market)) = market_entry else { continue };
+            let market_id = market_id.clone();
+
+            let base = TradeRecord::from_signal(&market_id, &signal, market);
+
+            if let Some(last_trade) = market.last_trade_time {
+                if last_trade.elapsed() < cooldown {
+                    let _ = trade_store.record(base.skipped("cooldown")).await;
+                    continue;
+                }
+            }
+
+            let (buy_token, buy_side, ask_price) = match signal.direction {
+                Direction::Up => (market.yes_token.clone(), "YES", market.yes_ask),
+                Direction::Down => (market.no_token.clone(), "NO", market.no_ask),
+            };
+            let Some(ask) = ask_price else {
+                let _ = trade_store.record(base.skipped("no_ask")).await;
+                continue;
+            };
+
+            let buy_tape = match signal.direction {
+                Direction::Up => &market.yes_tape,
+                Direction::Down => &market.no_tape,
+            };
+            let flow_volume = buy_tape.traded_volume();
+            if flow_volume < min_flow_volume {
+                let _ = trade_store.record(base.skipped(format!("flow {:.1}<{:.1}", flow_volume, min_flow_volume))).await;
+                continue;
+            }
+
+            let expiry = market.expiry_minutes;
+            let estimated_fair = fair_value_model.fair_cents(&signal, market, expiry);
+            let cushion_cents = bps_to_cents_rounded(spread_bps);
+            let edge = estimated_fair - ask - cushion_cents;
+
+            if edge < edge_threshold {
+                let _ = trade_store.record(base.skipped(format!("edge {}<{}", edge, edge_threshold))).await;
+                continue;
+            }
+
+            let price = ask as f64 / 100.0;
+            let contracts = size / price;
+            let current_inventory = state.positions.get(buy_token.as_str()).map(|p| p.contracts).unwrap_or(0.0);
+            if current_inventory + contracts > max_inventory {
+                let _ = trade_store.record(base.skipped("max_inventory")).await;
+                continue;
+            }
+
+            market.last_trade_time = Some(Instant::now());
+            state.positions
+                .entry(buy_token.clone())
+                .and_modify(|p| {
+                    let total = p.contracts + contracts;
+                    if total > 0.0 {
+                        p.avg_entry_cents = (((p.avg_entry_cents as f64 * p.contracts)
+                            + (ask as f64 * contracts))
+                            / total)
+                            .round() as i64;
+                    }
+                    p.contracts = total;
+                })
+                .or_insert(Position {
+                    token: buy_token,
+                    contracts,
+                    avg_entry_cents: ask,
+                    opened_at: Instant::now(),
+                });
+
+            report.trades += 1;
+
+            let _ = trade_store.record(TradeRecord {
+                outcome: TradeOutcome::DryRun,
+                side: Some(buy_side.to_string()),
+                price_cents: Some(ask),
+                size: Some(size),
+                ..base
+            }).await;
+        }
+
+        // Evaluate open positions against the same take-profit/stop-loss/
+        // max-hold logic the live exit evaluator uses, so replay actually
+        // exercises the exit path instead of only ever opening positions.
+        let to_close: Vec<(String, Position, String, &'static str, i64)> = state
+            .positions
+            .iter()
+            .filter_map(|(token, pos)| {
+                let market = state.markets.values().find(|m| &m.yes_token == token || &m.no_token == token)?;
+                let is_yes = &market.yes_token == token;
+                let best_bid = if is_yes { market.yes_bid } else { market.no_bid }?;
+                let side = if is_yes { "YES" } else { "NO" };
+                Some((token.clone(), pos.clone(), market.condition_id.clone(), side, best_bid))
+            })
+            .collect();
+
+        for (token, pos, market_id, side, bid) in to_close {
+            let edge = bid - pos.avg_entry_cents;
+            let Some(reason) = evaluate_exit(edge, pos.opened_at.elapsed(), take_profit_cents, stop_loss_cents, max_hold) else {
+                continue;
+            };
+            let asset = state.markets.get(&market_id).map(|m| m.asset.clone()).unwrap_or_default();
+            report.paper_pnl_cents += (bid - pos.avg_entry_cents) as f64 * pos.contracts;
+            let _ = trade_store.record(TradeRecord::from_exit(
+                &market_id, &asset, side, pos.avg_entry_cents, bid, pos.contracts, reason,
+            )).await;
+            state.positions.remove(&token);
+        }
+    }
+
+    // Mark any positions still open at EOF to their last known bid — they
+    // never crossed take-profit/stop-loss/max-hold before the capture ended.
+    let mut open_at_eof = 0usize;
+    for (token, pos) in &state.positions {
+        let Some(market) = state.markets.values().find(|m| &m.yes_token == token || &m.no_token == token) else { continue };
+        let is_yes = &market.yes_token == token;
+        let Some(bid) = (if is_yes { market.yes_bid } else { market.no_bid }) else { continue };
+        report.paper_pnl_cents += (bid - pos.avg_entry_cents) as f64 * pos.contracts;
+        open_at_eof += 1;
+    }
+
+    info!("═══════════════════════════════════════════════════════════════════════");
+    info!("📼 BACKTEST COMPLETE");
+    info!("   Signals seen:  {}", report.signals);
+    info!("   Trades taken:  {} ({:.1}% hit rate)", report.trades, report.hit_rate() * 100.0);
+    if open_at_eof > 0 {
+        info!("   Open at EOF:   {} (marked to last bid)", open_at_eof);
+    }
+    info!("   Paper P&L:     ${:.2}", report.paper_pnl_cents / 100.0);
+    info!("═══════════════════════════════════════════════════════════════════════");
+
+    Ok(())
+}
+
+// === Main ===
+
+#[tokio::main]
 async fn main() -> Result<()> {
     use clap::Parser;
 
@@ -490,18 +2573,25 @@ async fn main() -> Result<()> {
 
     let args = Args::parse();
 
+    if let Some(path) = args.replay_from.clone() {
+        return run_backtest(&args, &path).await;
+    }
+
     info!("═══════════════════════════════════════════════════════════════════════");
     info!("🚀 POLYMARKET MOMENTUM FRONT-RUNNER");
     info!("═══════════════════════════════════════════════════════════════════════");
     info!("STRATEGY:");
-    info!("   1. Detect rapid price moves (>{}bps in {}s)", args.threshold_bps, args.window_secs);
+    info!("   1. Detect vol-adjusted price moves (z>={:.1} over {}s)", args.z_min, args.window_secs);
     info!("   2. Buy YES on up moves, NO on down moves");
     info!("   3. Front-run slow market makers");
     info!("═══════════════════════════════════════════════════════════════════════");
     info!("CONFIG:");
     info!("   Mode: {}", if args.live { "🔴 LIVE" } else { "🔍 DRY RUN" });
     info!("   Size: ${:.2} per trade", args.size);
-    info!("   Threshold: {}bps ({}%)", args.threshold_bps, args.threshold_bps as f64 / 100.0);
+    info!("   Z-min: {:.1}", args.z_min);
+    if args.threshold_bps > 0 {
+        info!("   Legacy threshold filter: {}bps ({}%)", args.threshold_bps, args.threshold_bps as f64 / 100.0);
+    }
     info!("   Window: {}s", args.window_secs);
     info!("   Min Edge: {}¢", args.edge);
     info!("   Cooldown: {}s", args.cooldown);
@@ -555,9 +2645,14 @@ async fn main() -> Result<()> {
         return Ok(());
     }
 
+    // Candle resolution for OHLC aggregation
+    let resolution = Resolution::parse(&args.candle_res)
+        .with_context(|| format!("invalid --candle-res '{}' (expected 1s, 5s, 1m)", args.candle_res))?;
+
     // Initialize state
     let state = Arc::new(RwLock::new({
         let mut s = State::new();
+        s.resolution = resolution;
         for m in discovered {
             let id = m.condition_id.clone();
             s.markets.insert(id, m);
@@ -565,13 +2660,69 @@ async fn main() -> Result<()> {
         s
     }));
 
-    // Start price feed with momentum detection
+    // Wire up the notifier, if configured
+    if let Some(url) = args.webhook_url.clone() {
+        let notifier: Arc<dyn Notifier> = Arc::new(WebhookNotifier::new(url)?);
+        let rx = state.read().await.events.subscribe();
+        spawn_notifier(rx, notifier);
+        info!("[NOTIFY] Webhook notifications enabled");
+    }
+
+    // Wire up the fills broadcast server, if configured
+    if let Some(addr) = args.fills_addr.clone() {
+        let peers: PeerMap = Arc::new(std::sync::Mutex::new(HashMap::new()));
+        let rx = state.read().await.events.subscribe();
+        spawn_fills_broadcaster(rx, peers.clone());
+        let state_fills = state.clone();
+        tokio::spawn(async move {
+            if let Err(e) = run_fills_server(addr, state_fills, peers).await {
+                error!("[FILLS] server failed: {}", e);
+            }
+        });
+    }
+
+    // Capture every inbound market-data/price-feed event to a file for later
+    // backtest replay via `--replay-from`, if configured.
+    let recorder = match args.record_to.clone() {
+        Some(path) => {
+            info!("[RECORD] Capturing market data and price ticks to {}", path);
+            Some(Arc::new(Recorder::open(&path)?))
+        }
+        None => None,
+    };
+
+    // Build the configured price sources
+    let mut sources: Vec<Box<dyn PriceSource>> = Vec::new();
+    for name in args.sources.split(',').map(|s| s.trim()).filter(|s| !s.is_empty()) {
+        match name {
+            "polygon" => sources.push(Box::new(PolygonSource { api_key: polygon_api_key.clone() })),
+            "kraken" => sources.push(Box::new(KrakenSource)),
+            other => anyhow::bail!("unknown price source '{}' (expected: polygon, kraken)", other),
+        }
+    }
+    if sources.is_empty() {
+        anyhow::bail!("no price sources configured");
+    }
+    let min_confirmations = args.min_confirmations.clamp(1, sources.len());
+    info!("[FEED] {} source(s), requiring {} confirmation(s)", sources.len(), min_confirmations);
+
+    // Start price feeds with cross-source confirmed momentum detection
     let state_price = state.clone();
-    let polygon_key = polygon_api_key.clone();
     let threshold = args.threshold_bps;
     let window = args.window_secs;
+    let z_min = args.z_min;
+    let recorder_price = recorder.clone();
+    let disconnect_alert_secs = args.disconnect_alert_secs;
     tokio::spawn(async move {
-        run_price_feed(state_price, &polygon_key, threshold, window).await;
+        run_price_feeds(state_price, sources, threshold, window, z_min, min_confirmations, recorder_price, disconnect_alert_secs).await;
+    });
+
+    // Roll markets over as they expire so the bot keeps trading unattended
+    let state_rollover = state.clone();
+    let rollover_asset = args.asset.clone();
+    let rollover_secs = args.rollover_secs;
+    tokio::spawn(async move {
+        run_rollover(state_rollover, rollover_asset, rollover_secs).await;
     });
 
     // Get token IDs for subscription
@@ -586,19 +2737,94 @@ async fn main() -> Result<()> {
     let size = args.size;
     let dry_run = !args.live;
     let cooldown = Duration::from_secs(args.cooldown);
+    let spread_bps = args.spread_bps;
+    let take_profit_cents = args.take_profit;
+    let stop_loss_cents = args.stop_loss;
+    let max_hold = Duration::from_secs(args.max_hold_secs);
+    let max_inventory = args.max_inventory;
+    let min_flow_volume = args.min_flow_volume;
+    let fair_value_model = make_fair_value_model(&args.model, args.k)?;
+    let trade_store = make_trade_store(args.db.as_deref())?;
+
+    // Connection and trade health metrics, optionally exposed for scraping.
+    let metrics = Arc::new(MetricsRegistry::default());
+    let ws_opened = metrics.counter("ws_opened_connections");
+    let ws_closed = metrics.counter("ws_closed_connections");
+    let trades_attempted = metrics.counter("trades_attempted");
+    let trades_filled = metrics.counter("trades_filled");
+    let trades_failed = metrics.counter("trades_failed");
+    let staleness_gauge = metrics.gauge("orderbook_staleness_ms");
+
+    tokio::spawn(run_staleness_gauge(state.clone(), staleness_gauge));
+
+    if let Some(addr) = args.metrics_addr.clone() {
+        let metrics = metrics.clone();
+        tokio::spawn(async move {
+            if let Err(e) = serve_metrics(metrics, addr).await {
+                error!("[METRICS] server failed: {}", e);
+            }
+        });
+    }
+
+    // Book mid-price candle persistence (disabled unless --pg-url is set).
+    let mut candle_agg = CandleAggregator::default();
+    let candles_tx = if let Some(url) = args.pg_url.clone() {
+        let store = PgCandleStore::connect(&url).await?;
+        let (tx, mut rx) = mpsc::unbounded_channel::<BookCandle>();
+        tokio::spawn(async move {
+            // Drain completed candles and flush them in small batches.
+            let mut batch: Vec<BookCandle> = Vec::new();
+            let mut flush = tokio::time::interval(Duration::from_secs(2));
+            loop {
+                tokio::select! {
+                    maybe = rx.recv() => match maybe {
+                        Some(c) => {
+                            batch.push(c);
+                            if batch.len() >= 256 {
+                                if let Err(e) = store.upsert_batch(&batch).await {
+                                    error!("[CANDLE] upsert failed: {}", e);
+                                }
+                                batch.clear();
+                            }
+                        }
+                        None => break,
+                    },
+                    _ = flush.tick() => {
+                        if !batch.is_empty() {
+                            if let Err(e) = store.upsert_batch(&batch).await {
+                                error!("[CANDLE] upsert failed: {}", e);
+                            }
+                            batch.clear();
+                        }
+                    }
+                }
+            }
+        });
+        info!("[CANDLE] Postgres candle persistence enabled");
+        Some(tx)
+    } else {
+        None
+    };
 
     // Main WebSocket loop
+    let ws_events = state.read().await.events.clone();
+    let mut ws_outage = DisconnectDebouncer::new(args.disconnect_alert_secs);
     loop {
         info!("[WS] Connecting to Polymarket...");
+        let connected_at = Instant::now();
 
         let (ws, _) = match connect_async(POLYMARKET_WS_URL).await {
             Ok(ws) => ws,
             Err(e) => {
                 error!("[WS] Connect failed: {}", e);
+                if ws_outage.on_disconnect(connected_at.elapsed()) {
+                    let _ = ws_events.send(BotEvent::FeedDisconnected { source: "polymarket".to_string() });
+                }
                 tokio::time::sleep(Duration::from_secs(5)).await;
                 continue;
             }
         };
+        ws_opened.inc();
 
         let (mut write, mut read) = ws.split();
 
@@ -612,6 +2838,8 @@ async fn main() -> Result<()> {
 
         let mut ping_interval = tokio::time::interval(Duration::from_secs(30));
         let mut signal_check = tokio::time::interval(Duration::from_millis(100));
+        let mut resub_check = tokio::time::interval(Duration::from_secs(1));
+        let mut exit_check = tokio::time::interval(Duration::from_millis(500));
 
         loop {
             tokio::select! {
@@ -622,9 +2850,109 @@ async fn main() -> Result<()> {
                     }
                 }
 
+                _ = resub_check.tick() => {
+                    // A rollover swapped markets; resubscribe to the new tokens.
+                    let dirty = {
+                        let mut s = state.write().await;
+                        std::mem::take(&mut s.subscription_dirty)
+                    };
+                    if dirty {
+                        let new_tokens: Vec<String> = {
+                            let s = state.read().await;
+                            s.markets.values()
+                                .flat_map(|m| vec![m.yes_token.clone(), m.no_token.clone()])
+                                .collect()
+                        };
+                        let sub = SubscribeCmd {
+                            assets_ids: new_tokens.clone(),
+                            sub_type: "market",
+                        };
+                        if let Err(e) = write.send(Message::Text(serde_json::to_string(&sub)?)).await {
+                            error!("[WS] Resubscribe failed: {}", e);
+                            break;
+                        }
+                        info!("[ROLLOVER] Resubscribed to {} tokens", new_tokens.len());
+                    }
+                }
+
+                _ = exit_check.tick() => {
+                    // Evaluate open positions against take-profit, stop-loss, and
+                    // max-hold, using each token's maintained best bid. Snapshot
+                    // first so the sell itself doesn't need the lock held.
+                    let mut s = state.write().await;
+
+                    let candidates: Vec<(String, Position, String, &'static str, Option<i64>, String)> = s
+                        .positions
+                        .iter()
+                        .filter_map(|(token, pos)| {
+                            let market = s.markets.values().find(|m| &m.yes_token == token || &m.no_token == token)?;
+                            let is_yes = &market.yes_token == token;
+                            let best_bid = if is_yes { market.yes_bid } else { market.no_bid };
+                            let side = if is_yes { "YES" } else { "NO" };
+                            Some((token.clone(), pos.clone(), market.asset.clone(), side, best_bid, market.condition_id.clone()))
+                        })
+                        .collect();
+
+                    for (token, pos, asset, side, best_bid, market_id) in candidates {
+                        let Some(bid) = best_bid else { continue };
+                        let edge = bid - pos.avg_entry_cents;
+                        let Some(reason) = evaluate_exit(edge, pos.opened_at.elapsed(), take_profit_cents, stop_loss_cents, max_hold) else {
+                            continue;
+                        };
+
+                        if dry_run {
+                            warn!("[DRY] Would SELL {:.2} {} {} @{}¢ | entry={}¢ edge={}¢ | reason={}",
+                                  pos.contracts, asset, side, bid, pos.avg_entry_cents, edge, reason);
+                            let _ = trade_store.record(TradeRecord::from_exit(
+                                &market_id, &asset, side, pos.avg_entry_cents, bid, pos.contracts, reason,
+                            )).await;
+                            s.positions.remove(&token);
+                            continue;
+                        }
+
+                        warn!("[EXIT] 🎯 SELL {:.2} {} {} @{}¢ | entry={}¢ edge={}¢ | reason={}",
+                              pos.contracts, asset, side, bid, pos.avg_entry_cents, edge, reason);
+
+                        let client = shared_client.clone();
+                        let state_clone = state.clone();
+                        let store = trade_store.clone();
+                        let price = bid as f64 / 100.0;
+                        let contracts = pos.contracts;
+                        let entry_cents = pos.avg_entry_cents;
+                        let reason = reason.to_string();
+
+                        tokio::spawn(async move {
+                            match client.sell_fak(&token, price, contracts).await {
+                                Ok(fill) => {
+                                    warn!("[EXIT] ✅ Sold {:.2} @${:.2} | order_id={} | reason={}",
+                                          fill.filled_size, fill.fill_cost, fill.order_id, reason);
+                                    let _ = store.record(TradeRecord::from_exit(
+                                        &market_id, &asset, side, entry_cents, bid, fill.filled_size, reason.clone(),
+                                    )).await;
+                                    let mut s = state_clone.write().await;
+                                    s.positions.remove(&token);
+                                    s.publish(BotEvent::PositionClosed {
+                                        token,
+                                        asset,
+                                        side: side.to_string(),
+                                        contracts: fill.filled_size,
+                                        entry_cents,
+                                        exit_cents: bid,
+                                        reason,
+                                    });
+                                }
+                                Err(e) => {
+                                    error!("[EXIT] ❌ Sell failed: {}", e);
+                                }
+                            }
+                        });
+                    }
+                }
+
                 _ = signal_check.tick() => {
                     // Process pending momentum signals
                     let mut s = state.write().await;
+                    let events = s.events.clone();
 
                     // Remove stale signals (>5s old)
                     s.pending_signals.retain(|sig| sig.triggered_at.elapsed() < Duration::from_secs(5));
@@ -633,20 +2961,32 @@ async fn main() -> Result<()> {
                     let signals: Vec<MomentumSignal> = s.pending_signals.drain(..).collect();
 
                     for signal in signals {
-                        // Find market for this asset
-                        let market_entry = s.markets.iter_mut()
+                        // Find market for this asset. Read-only: `s.positions` needs
+                        // its own immutable borrow of `s` further down (inventory cap
+                        // check), and that can't coexist with a `s.markets` mutable
+                        // borrow held across the whole loop body — `last_trade_time`
+                        // is updated via a fresh `get_mut` by id instead.
+                        let market_entry = s.markets.iter()
                             .find(|(_, m)| m.asset == signal.asset);
 
                         let Some((market_id, market)) = market_entry else {
                             continue;
                         };
 
+                        // Base record capturing the orderbook snapshot at decision time
+                        let base = TradeRecord::from_signal(market_id, &signal, market);
+
                         // Check cooldown
                         if let Some(last_trade) = market.last_trade_time {
                             if last_trade.elapsed() < cooldown {
                                 info!("[COOLDOWN] {} - {}s remaining",
                                       signal.asset,
                                       (cooldown - last_trade.elapsed()).as_secs());
+                                let _ = trade_store.record(base.skipped("cooldown")).await;
+                                let _ = events.send(BotEvent::TradeSkipped {
+                                    asset: signal.asset.clone(),
+                                    reason: "cooldown".to_string(),
+                                });
                                 continue;
                             }
                         }
@@ -665,18 +3005,52 @@ async fn main() -> Result<()> {
 
                         let Some(ask) = ask_price else {
                             warn!("[SKIP] {} {} - no ask price", signal.asset, buy_side);
+                            let _ = trade_store.record(base.skipped("no_ask")).await;
+                            let _ = events.send(BotEvent::TradeSkipped {
+                                asset: signal.asset.clone(),
+                                reason: "no_ask".to_string(),
+                            });
                             continue;
                         };
 
-                        // For momentum trades, we expect fair value to be moving
-                        // If price moved up 15bps, YES should be worth more than 50¢
-                        // Rough estimate: each 10bps move = ~1¢ edge in short-term
-                        let estimated_fair = 50 + (signal.move_bps.abs() / 10) as i64;
-                        let edge = estimated_fair - ask;
+                        // Require real prints backing the move, not just a quote
+                        // flicker: the token we'd buy must have traded enough
+                        // volume in its recent tape.
+                        let buy_tape = match signal.direction {
+                            Direction::Up => &market.yes_tape,
+                            Direction::Down => &market.no_tape,
+                        };
+                        let flow_volume = buy_tape.traded_volume();
+                        if flow_volume < min_flow_volume {
+                            info!("[SKIP] {} {} - traded volume {:.1} < {:.1} min_flow_volume",
+                                  signal.asset, buy_side, flow_volume, min_flow_volume);
+                            let _ = trade_store.record(base.skipped(format!("flow {:.1}<{:.1}", flow_volume, min_flow_volume))).await;
+                            let _ = events.send(BotEvent::TradeSkipped {
+                                asset: signal.asset.clone(),
+                                reason: "no_flow".to_string(),
+                            });
+                            continue;
+                        }
+
+                        // Fair value comes from the selected model; the spread
+                        // cushion (bps → cents, rounded to the nearest cent) is
+                        // subtracted so the fill must clear both the model edge
+                        // and the cushion.
+                        let expiry = market.expiry_minutes;
+                        let estimated_fair = fair_value_model.fair_cents(&signal, market, expiry);
+                        let cushion_cents = bps_to_cents_rounded(spread_bps);
+                        let edge = estimated_fair - ask - cushion_cents;
 
                         if edge < edge_threshold {
-                            info!("[SKIP] {} {} - edge {}¢ < {}¢ threshold (ask={}¢, est_fair={}¢)",
-                                  signal.asset, buy_side, edge, edge_threshold, ask, estimated_fair);
+                            info!("[SKIP] {} {} - edge {}¢ < {}¢ threshold (ask={}¢, est_fair={}¢, cushion={}¢)",
+                                  signal.asset, buy_side, edge, edge_threshold, ask, estimated_fair, cushion_cents);
+                            let _ = trade_store.record(
+                                base.skipped(format!("edge {}<{}", edge, edge_threshold))
+                            ).await;
+                            let _ = events.send(BotEvent::TradeSkipped {
+                                asset: signal.asset.clone(),
+                                reason: format!("edge {}<{}", edge, edge_threshold),
+                            });
                             continue;
                         }
 
@@ -684,38 +3058,155 @@ async fn main() -> Result<()> {
                         let buy_token_clone = buy_token.clone();
 
                         if dry_run {
+                            // Same per-token inventory cap as the live path, applied to paper fills.
+                            let contracts = size / (ask as f64 / 100.0);
+                            let current_inventory = s.positions.get(buy_token.as_str()).map(|p| p.contracts).unwrap_or(0.0);
+                            if current_inventory + contracts > max_inventory {
+                                let _ = trade_store.record(base.skipped("max_inventory")).await;
+                                let _ = events.send(BotEvent::TradeSkipped {
+                                    asset: signal.asset.clone(),
+                                    reason: "max_inventory".to_string(),
+                                });
+                                continue;
+                            }
                             warn!("[DRY] 🎯 Would BUY ${:.0} {} {} @{}¢ | move={}bps | edge={}¢",
                                   size, signal.asset, buy_side, ask, signal.move_bps.abs(), edge);
-                            market.last_trade_time = Some(Instant::now());
+                            let _ = trade_store.record(TradeRecord {
+                                outcome: TradeOutcome::DryRun,
+                                side: Some(buy_side.to_string()),
+                                price_cents: Some(ask),
+                                size: Some(size),
+                                ..base.clone()
+                            }).await;
+                            if let Some(m) = s.markets.get_mut(&market_id_clone) {
+                                m.last_trade_time = Some(Instant::now());
+                            }
+
+                            // Paper-fill the position so the take-profit/stop-loss/
+                            // max-hold exit evaluator (and the [DRY] Would SELL
+                            // branch) has something to evaluate in the default,
+                            // non-live mode.
+                            s.positions
+                                .entry(buy_token_clone.clone())
+                                .and_modify(|p| {
+                                    let total = p.contracts + contracts;
+                                    if total > 0.0 {
+                                        p.avg_entry_cents = (((p.avg_entry_cents as f64 * p.contracts)
+                                            + (ask as f64 * contracts))
+                                            / total)
+                                            .round() as i64;
+                                    }
+                                    p.contracts = total;
+                                })
+                                .or_insert(Position {
+                                    token: buy_token_clone.clone(),
+                                    contracts,
+                                    avg_entry_cents: ask,
+                                    opened_at: Instant::now(),
+                                });
                         } else {
                             let price = ask as f64 / 100.0;
                             let contracts = size / price;
 
+                            // Global inventory cap: stop adding to a token once fully allocated.
+                            let current_inventory = s.positions
+                                .get(buy_token.as_str())
+                                .map(|p| p.contracts)
+                                .unwrap_or(0.0);
+                            if current_inventory + contracts > max_inventory {
+                                info!("[SKIP] {} {} - inventory {:.1}+{:.1} would exceed cap {:.1}",
+                                      signal.asset, buy_side, current_inventory, contracts, max_inventory);
+                                let _ = trade_store.record(base.skipped("max_inventory")).await;
+                                let _ = events.send(BotEvent::TradeSkipped {
+                                    asset: signal.asset.clone(),
+                                    reason: "max_inventory".to_string(),
+                                });
+                                continue;
+                            }
+
                             warn!("[TRADE] 🎯 BUY ${:.0} {} {} @{}¢ | move={}bps | edge={}¢",
                                   size, signal.asset, buy_side, ask, signal.move_bps.abs(), edge);
 
+                            let _ = events.send(BotEvent::TradeSubmitted {
+                                asset: signal.asset.clone(),
+                                side: buy_side.to_string(),
+                                price_cents: ask,
+                                size,
+                            });
+
                             let client = shared_client.clone();
                             let state_clone = state.clone();
+                            let store = trade_store.clone();
+                            let trades_filled = trades_filled.clone();
+                            let trades_failed = trades_failed.clone();
+                            trades_attempted.inc();
+                            let submitted = TradeRecord {
+                                outcome: TradeOutcome::Submitted,
+                                side: Some(buy_side.to_string()),
+                                price_cents: Some(ask),
+                                size: Some(size),
+                                ..base.clone()
+                            };
 
                             // Execute trade asynchronously
                             tokio::spawn(async move {
                                 match client.buy_fak(&buy_token_clone, price, contracts).await {
                                     Ok(fill) => {
+                                        trades_filled.inc();
                                         warn!("[TRADE] ✅ Filled {:.2} @${:.2} | order_id={}",
                                               fill.filled_size, fill.fill_cost, fill.order_id);
 
+                                        let fill_asset = submitted.asset.clone();
+                                        let fill_side = submitted.side.clone().unwrap_or_default();
+                                        let _ = store.record(TradeRecord {
+                                            outcome: TradeOutcome::Filled,
+                                            filled_size: Some(fill.filled_size),
+                                            fill_cost: Some(fill.fill_cost),
+                                            ..submitted
+                                        }).await;
+
                                         let mut s = state_clone.write().await;
                                         if let Some(m) = s.markets.get_mut(&market_id_clone) {
                                             m.last_trade_time = Some(Instant::now());
                                         }
+                                        s.positions
+                                            .entry(buy_token_clone.clone())
+                                            .and_modify(|p| {
+                                                let total = p.contracts + fill.filled_size;
+                                                if total > 0.0 {
+                                                    p.avg_entry_cents = (((p.avg_entry_cents as f64 * p.contracts)
+                                                        + (ask as f64 * fill.filled_size))
+                                                        / total)
+                                                        .round() as i64;
+                                                }
+                                                p.contracts = total;
+                                            })
+                                            .or_insert(Position {
+                                                token: buy_token_clone.clone(),
+                                                contracts: fill.filled_size,
+                                                avg_entry_cents: ask,
+                                                opened_at: Instant::now(),
+                                            });
+                                        s.publish(BotEvent::TradeFilled {
+                                            market_id: market_id_clone.clone(),
+                                            asset: fill_asset,
+                                            side: fill_side,
+                                            filled_size: fill.filled_size,
+                                            fill_cost: fill.fill_cost,
+                                            order_id: fill.order_id.clone(),
+                                        });
                                     }
                                     Err(e) => {
+                                        trades_failed.inc();
                                         error!("[TRADE] ❌ Buy failed: {}", e);
+                                        let _ = store.record(submitted.skipped(format!("buy_failed: {}", e))).await;
                                     }
                                 }
                             });
 
-                            market.last_trade_time = Some(Instant::now());
+                            if let Some(m) = s.markets.get_mut(&market_id_clone) {
+                                m.last_trade_time = Some(Instant::now());
+                            }
                         }
                     }
                 }
@@ -725,39 +3216,32 @@ async fn main() -> Result<()> {
 
                     match msg {
                         Ok(Message::Text(text)) => {
-                            // Update orderbook
-                            if let Ok(books) = serde_json::from_str::<Vec<BookSnapshot>>(&text) {
+                            if let Some(recorder) = &recorder {
+                                recorder.record(CapturedEvent::Book { raw: text.clone() });
+                            }
+
+                            // Update orderbook: full snapshots replace the ladder outright;
+                            // deltas apply (or buffer/drop-resync) through `OrderBookSide`;
+                            // trade prints update the tape and the level they crossed.
+                            // `apply_book_event` is shared with the backtest replay driver
+                            // so both run identical update code.
+                            if let Ok(events) = serde_json::from_str::<Vec<BookEvent>>(&text) {
                                 let mut s = state.write().await;
 
-                                for book in books {
-                                    // Find market
-                                    let market = s.markets.values_mut()
-                                        .find(|m| m.yes_token == book.asset_id || m.no_token == book.asset_id);
-
-                                    let Some(market) = market else { continue };
-
-                                    let best_ask = book.asks.iter()
-                                        .filter_map(|l| {
-                                            let price = parse_price_cents(&l.price);
-                                            if price > 0 { Some(price) } else { None }
-                                        })
-                                        .min();
-
-                                    let best_bid = book.bids.iter()
-                                        .filter_map(|l| {
-                                            let price = parse_price_cents(&l.price);
-                                            if price > 0 { Some(price) } else { None }
-                                        })
-                                        .max();
-
-                                    let is_yes = book.asset_id == market.yes_token;
-
-                                    if is_yes {
-                                        market.yes_ask = best_ask;
-                                        market.yes_bid = best_bid;
-                                    } else {
-                                        market.no_ask = best_ask;
-                                        market.no_bid = best_bid;
+                                for event in events {
+                                    let Some(market_id) = s.apply_book_event(event) else { continue };
+
+                                    // Fold the fresh YES mid into OHLC candles.
+                                    if let Some(tx) = &candles_tx {
+                                        if let Some(market) = s.markets.get(&market_id) {
+                                            if let (Some(bid), Some(ask)) = (market.yes_bid, market.yes_ask) {
+                                                let mid = (bid + ask) as f64 / 2.0;
+                                                let now = Utc::now().timestamp();
+                                                for candle in candle_agg.observe(&market_id, mid, now) {
+                                                    let _ = tx.send(candle);
+                                                }
+                                            }
+                                        }
                                     }
                                 }
                             }
@@ -772,7 +3256,67 @@ async fn main() -> Result<()> {
             }
         }
 
+        ws_closed.inc();
+        if ws_outage.on_disconnect(connected_at.elapsed()) {
+            let _ = ws_events.send(BotEvent::FeedDisconnected { source: "polymarket".to_string() });
+        }
         info!("[WS] Disconnected, reconnecting in 3s...");
         tokio::time::sleep(Duration::from_secs(3)).await;
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn signal(move_bps: i64) -> MomentumSignal {
+        MomentumSignal {
+            asset: "BTC".to_string(),
+            direction: if move_bps >= 0 { Direction::Up } else { Direction::Down },
+            move_bps,
+            triggered_at: Instant::now(),
+        }
+    }
+
+    fn market() -> Market {
+        Market {
+            condition_id: "c".to_string(),
+            question: "q".to_string(),
+            yes_token: "y".to_string(),
+            no_token: "n".to_string(),
+            asset: "BTC".to_string(),
+            expiry_minutes: Some(15.0),
+            yes_ask: None,
+            yes_bid: None,
+            no_ask: None,
+            no_bid: None,
+            yes_book: OrderBookSide::default(),
+            no_book: OrderBookSide::default(),
+            yes_tape: TradeTape::default(),
+            no_tape: TradeTape::default(),
+            last_trade_time: None,
+            discovered_at: Instant::now(),
+        }
+    }
+
+    #[test]
+    fn linear_model_matches_legacy_rule() {
+        let m = LinearMoveModel;
+        assert_eq!(m.fair_cents(&signal(50), &market(), Some(15.0)), 55);
+        assert_eq!(m.fair_cents(&signal(-50), &market(), Some(15.0)), 55);
+    }
+
+    #[test]
+    fn time_decay_grows_near_expiry() {
+        let m = TimeDecayModel { k: 0.1 };
+        let early = m.fair_cents(&signal(50), &market(), Some(15.0));
+        let late = m.fair_cents(&signal(50), &market(), Some(1.0));
+        assert!(late > early, "late={late} should exceed early={early}");
+    }
+
+    #[test]
+    fn fair_value_is_clamped() {
+        let m = TimeDecayModel { k: 10.0 };
+        assert_eq!(m.fair_cents(&signal(9999), &market(), Some(0.1)), 99);
+    }
+}